@@ -1,20 +1,21 @@
 CONTRACT_ADDR=$(<./scripts/contract-address-junox)
 
 ADMIN="${SENDER:-juno1zk4c4aamef42cgjexlmksypac8j5xw7n3s4wrd}"
-LAST_BLOCK=2123734
-
-MEADOW='{"type": "meadow", "created_at": 2123734}'
-SUNFLOWER_1='{"type": "field", "created_at": 2123734, "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": 2123734, "watered_at": [1,1,1,1,1]} }'
-SUNFLOWER_2='{"type": "field", "created_at": 2123734, "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": 2123734, "watered_at": [1,1,1,1,1]} }'
-SUNFLOWER_3='{"type": "field", "created_at": 2123734, "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": 2123734, "watered_at": [1,1,1,1,1]} }'
-SUNFLOWER_4='{"type": "field", "created_at": 2123734, "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": 2123734, "watered_at": [1,1,1,1,1]} }'
-SUNFLOWER_5='{"type": "field", "created_at": 2123734, "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": 2123734, "watered_at": [1,1,1,1,1]} }'
-
-WHEAT_1='{"type": "field", "created_at": 2123734, "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": 2123734, "watered_at": [1,1,1,1]} }'
-WHEAT_2='{"type": "field", "created_at": 2123734, "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": 2123734, "watered_at": [1,1,1,1]} }'
-WHEAT_3='{"type": "field", "created_at": 2123734, "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": 2123734, "watered_at": [1,1,1,1]} }'
-WHEAT_4='{"type": "field", "created_at": 2123734, "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": 2123734, "watered_at": [1,1,1,1]} }'
-WHEAT_5='{"type": "field", "created_at": 2123734, "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": 2123734, "watered_at": [1,1,1,1]} }'
+# SetupFarm rejects timers in the future, use the latest block.
+LAST_BLOCK=$(junod status --node https://juno-testnet-rpc.polkachu.com:443 2>&1 | jq -r '.SyncInfo.latest_block_height')
+
+MEADOW='{"type": "meadow", "created_at": '"${LAST_BLOCK}"'}'
+SUNFLOWER_1='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1,1]} }'
+SUNFLOWER_2='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1,1]} }'
+SUNFLOWER_3='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1,1]} }'
+SUNFLOWER_4='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1,1]} }'
+SUNFLOWER_5='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "sunflower", "growth_period": 10, "stages": 5, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1,1]} }'
+
+WHEAT_1='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1]} }'
+WHEAT_2='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1]} }'
+WHEAT_3='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1]} }'
+WHEAT_4='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1]} }'
+WHEAT_5='{"type": "field", "created_at": '"${LAST_BLOCK}"', "plant": {"type": "wheat", "growth_period": 10, "stages": 4, "created_at": '"${LAST_BLOCK}"', "watered_at": [1,1,1,1]} }'
 
 SETUP_FARM_MSG=$(cat <<-END
     {
@@ -0,0 +1,249 @@
+use cosmwasm_std::{
+    coin, to_binary, BankMsg, BlockInfo, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response,
+    Storage,
+};
+
+use crate::{
+    helpers::{burn_nft_msg, mint_crop_tokens_msg, mint_seeds, transfer_nft_msg},
+    msg::{FarmAction, FarmActionResult, PausableAction},
+    params,
+    pause::ensure_action_enabled,
+    randomness::request_randomness,
+    season::add_season_points,
+    state::{
+        load_plant_config, points, sync_crop_points, FarmProfile, NoiseJob, Points, CROP_TOKENS,
+        NOIS_PROXY,
+    },
+    ContractError,
+};
+
+/// Loads the farm with the plots needed to act on `area`.
+pub fn load_farm(
+    storage: &dyn Storage,
+    owner: &str,
+    area: &[(u8, u8)],
+) -> Result<FarmProfile, ContractError> {
+    FarmProfile::load(storage, owner, area)?.ok_or_else(|| ContractError::NoFarm {
+        address: owner.to_string(),
+    })
+}
+
+/// Tills the plot and returns the message refunding the seed NFT of a dead plant.
+pub fn till_plot(
+    farm: &mut FarmProfile,
+    owner: &str,
+    x: u8,
+    y: u8,
+    now: u64,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let dead_plant = farm.till(x.into(), y.into(), now)?;
+
+    match dead_plant.and_then(|plant| plant.seed_nft) {
+        None => Ok(vec![]),
+        Some(nft) => Ok(vec![transfer_nft_msg(&nft, owner.to_string())?]),
+    }
+}
+
+/// Harvests the plant, credits the points and returns the messages minting the seeds and crop tokens.
+/// Every check runs before the farm or the storage is changed, the caller saves the farm.
+pub fn harvest_plot(
+    storage: &mut dyn Storage,
+    farm: &mut FarmProfile,
+    owner: &str,
+    x: u8,
+    y: u8,
+    block: &BlockInfo,
+    funds: Vec<Coin>,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    ensure_action_enabled(storage, PausableAction::Harvest)?;
+
+    let now = farm.now(block);
+    let plant = farm.harvestable_plant(x.into(), y.into(), now)?;
+    let komple = plant
+        .komple
+        .clone()
+        .ok_or(ContractError::PlantKompleMissing {
+            x: x.into(),
+            y: y.into(),
+        })?;
+
+    let config = load_plant_config(storage, &plant.r#type)?;
+    let (min_seeds, max_seeds) = plant.seed_yield(&config);
+    let harvest_points = plant.harvest_points(&config);
+
+    let mut harvest_messages = vec![];
+
+    // The seed was consumed by the harvest.
+    if let Some(nft) = &plant.seed_nft {
+        harvest_messages.push(burn_nft_msg(nft)?);
+    }
+
+    if let Some(token) = CROP_TOKENS.may_load(storage, plant.r#type.as_str())? {
+        if !config.produce.is_zero() {
+            harvest_messages.push(mint_crop_tokens_msg(
+                &token,
+                owner.to_string(),
+                config.produce,
+            )?);
+        }
+    }
+
+    // Requesting randomness stores the job, so it runs once nothing else can fail.
+    let mut messages = match NOIS_PROXY.may_load(storage)? {
+        None => mint_seeds(komple, owner.to_string(), min_seeds, storage)?,
+        Some(_) => {
+            let job = NoiseJob::MintSeeds {
+                plant: komple,
+                recipient: owner.to_string(),
+                min_seeds,
+                max_seeds,
+            };
+            let (_, msg) = request_randomness(storage, job, block.height, funds)?;
+
+            vec![msg]
+        }
+    };
+    messages.extend(harvest_messages);
+
+    let harvested = farm.harvest(x.into(), y.into(), now)?;
+
+    let mut pts = points()
+        .may_load(storage, owner)?
+        .unwrap_or_else(|| Points::new(owner.to_string()));
+    pts.add(&harvested, harvest_points);
+    points().save(storage, owner, &pts)?;
+    sync_crop_points(storage, &pts, harvested.as_str())?;
+    add_season_points(storage, owner, harvest_points, block.height)?;
+
+    Ok(messages)
+}
+
+/// Splits the funds evenly between the harvests of a batch.
+fn harvest_funds(funds: &[Coin], harvests: u128) -> Result<Vec<Coin>, ContractError> {
+    if funds.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if harvests == 0 {
+        return Err(ContractError::InvalidBatch {
+            reason: "funds can only be sent along with harvests".to_string(),
+        });
+    }
+
+    funds
+        .iter()
+        .map(|fund| {
+            if fund.amount.u128() % harvests != 0 {
+                return Err(ContractError::InvalidBatch {
+                    reason: "funds must split evenly between the harvests".to_string(),
+                });
+            }
+
+            Ok(coin(fund.amount.u128() / harvests, &fund.denom))
+        })
+        .collect()
+}
+
+pub fn execute_batch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    actions: Vec<FarmAction>,
+    skip_failures: bool,
+) -> Result<Response, ContractError> {
+    if actions.is_empty() || actions.len() > params::MAX_BATCH_ACTIONS {
+        return Err(ContractError::InvalidBatch {
+            reason: format!(
+                "between 1 and {} actions are allowed",
+                params::MAX_BATCH_ACTIONS
+            ),
+        });
+    }
+
+    let owner = info.sender.to_string();
+    let area: Vec<(u8, u8)> = actions
+        .iter()
+        .map(|action| match action {
+            FarmAction::Till { x, y }
+            | FarmAction::Water { x, y }
+            | FarmAction::Harvest { x, y } => (*x, *y),
+        })
+        .collect();
+    let mut farm = load_farm(deps.storage, &owner, &area)?;
+    let now = farm.now(&env.block);
+
+    let harvests = actions
+        .iter()
+        .filter(|action| matches!(action, FarmAction::Harvest { .. }))
+        .count() as u128;
+    let funds = harvest_funds(&info.funds, harvests)?;
+
+    let mut messages = vec![];
+    let mut results = vec![];
+    let mut harvested = 0;
+    for action in actions {
+        let result = match action {
+            FarmAction::Till { x, y } => till_plot(&mut farm, &owner, x, y, now),
+            FarmAction::Water { x, y } => farm.water_plant(x.into(), y.into(), now).map(|_| vec![]),
+            FarmAction::Harvest { x, y } => harvest_plot(
+                deps.storage,
+                &mut farm,
+                &owner,
+                x,
+                y,
+                &env.block,
+                funds.clone(),
+            ),
+        };
+
+        match result {
+            Ok(msgs) => {
+                if matches!(action, FarmAction::Harvest { .. }) {
+                    harvested += 1;
+                }
+                messages.extend(msgs);
+                results.push(FarmActionResult {
+                    action,
+                    error: None,
+                });
+            }
+            Err(err) if skip_failures => results.push(FarmActionResult {
+                action,
+                error: Some(err.to_string()),
+            }),
+            Err(err) => return Err(err),
+        }
+    }
+
+    farm.save(deps.storage, &owner)?;
+
+    // Only harvests requesting randomness spend their share of the funds.
+    let spent = match NOIS_PROXY.may_load(deps.storage)? {
+        None => 0,
+        Some(_) => harvested,
+    };
+    if !funds.is_empty() && spent < harvests {
+        messages.push(
+            BankMsg::Send {
+                to_address: owner,
+                amount: funds
+                    .iter()
+                    .map(|fund| coin(fund.amount.u128() * (harvests - spent), &fund.denom))
+                    .collect(),
+            }
+            .into(),
+        );
+    }
+
+    let failed = results
+        .iter()
+        .filter(|result| result.error.is_some())
+        .count();
+
+    Ok(Response::new()
+        .add_attribute("action", "batch")
+        .add_attribute("succeeded", (results.len() - failed).to_string())
+        .add_attribute("failed", failed.to_string())
+        .add_messages(messages)
+        .set_data(to_binary(&results)?))
+}
@@ -0,0 +1,99 @@
+use cosmwasm_std::{Deps, DepsMut, MessageInfo, Response, StdResult};
+
+use crate::{
+    helpers::admin_error_check,
+    msg::Role,
+    state::{INFORMATION, PENDING_ADMIN, ROLES},
+    ContractError,
+};
+
+pub fn propose_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    admin_error_check(deps.as_ref(), info)?;
+
+    match &admin {
+        None => PENDING_ADMIN.remove(deps.storage),
+        Some(admin) => {
+            deps.api.addr_validate(admin)?;
+            PENDING_ADMIN.save(deps.storage, admin)?;
+        }
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin")
+        .add_attribute("admin", admin.unwrap_or_default()))
+}
+
+pub fn accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let pending = PENDING_ADMIN
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoPendingAdmin {})?;
+    if info.sender != pending {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let mut contract_info = INFORMATION.load(deps.storage)?;
+    contract_info.admin = pending;
+    INFORMATION.save(deps.storage, &contract_info)?;
+    PENDING_ADMIN.remove(deps.storage);
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("admin", contract_info.admin))
+}
+
+pub fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    admin_error_check(deps.as_ref(), info)?;
+    let address = deps.api.addr_validate(&address)?;
+
+    let mut roles = ROLES
+        .may_load(deps.storage, address.as_str())?
+        .unwrap_or_default();
+    if !roles.contains(&role) {
+        roles.push(role.clone());
+    }
+    ROLES.save(deps.storage, address.as_str(), &roles)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "grant_role")
+        .add_attribute("address", address)
+        .add_attribute("role", role.to_string()))
+}
+
+pub fn revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    role: Role,
+) -> Result<Response, ContractError> {
+    admin_error_check(deps.as_ref(), info)?;
+
+    let mut roles = ROLES
+        .may_load(deps.storage, address.as_str())?
+        .unwrap_or_default();
+    roles.retain(|granted| *granted != role);
+    if roles.is_empty() {
+        ROLES.remove(deps.storage, address.as_str());
+    } else {
+        ROLES.save(deps.storage, address.as_str(), &roles)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "revoke_role")
+        .add_attribute("address", address)
+        .add_attribute("role", role.to_string()))
+}
+
+pub fn query_roles(deps: Deps, address: String) -> StdResult<Vec<Role>> {
+    Ok(ROLES
+        .may_load(deps.storage, address.as_str())?
+        .unwrap_or_default())
+}
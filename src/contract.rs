@@ -1,77 +1,197 @@
-use std::collections::HashMap;
-
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    ensure_eq, to_binary, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult, Storage, WasmMsg,
+    to_binary, Api, BankMsg, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+    StdResult,
 };
-use cw2::set_contract_version;
-use komple_framework_mint_module::msg::ExecuteMsg as KompleMintExecuteMsg;
-use nois::{int_in_range, NoisCallback, ProxyExecuteMsg};
+use cw2::{get_contract_version, set_contract_version};
+use cw_storage_plus::Bound;
+use semver::Version;
 
+use crate::actions::{execute_batch, harvest_plot, load_farm, till_plot};
+use crate::admin::{accept_admin, grant_role, propose_admin, query_roles, revoke_role};
 use crate::error::ContractError;
-use crate::farm::KomplePlant;
-use crate::msg::{ContractInformation, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::farm::{ClockMode, PlantType};
+use crate::msg::{
+    ContractInformation, CropEventsConfig, CropTokenResponse, ExecuteMsg, ExpansionConfig, FarmNft,
+    InstantiateMsg, MarketConfig, MigrateMsg, NoisConfig, PaymentMethod, PendingNoisJobResponse,
+    PlantTypeResponse, QueryMsg, Role, SeedPriceResponse,
+};
 
-use crate::helpers::throw_err;
+use crate::helpers::{admin_error_check, role_error_check, transfer_nft_msg, treasury};
+use crate::leaderboard::{query_leaderboard, query_rank, query_season_leaderboard};
+use crate::market::{
+    buy_listing, cancel_listing, query_listings, receive_cw20, update_market_config,
+};
+use crate::params;
+use crate::pause::{ensure_not_paused, load_pause_status, set_action_paused, set_paused};
+use crate::prizes::{distribute_prizes, fund_prize_pool, update_prize_config};
+use crate::randomness::{nois_receive, retry_job, roll_crop_events, settle_job};
 use crate::receive::receive;
+use crate::season::{
+    active_season, close_season, create_season, load_season, query_season_results, query_seasons,
+};
+use crate::shop::{buy_seeds, set_seed_price};
 use crate::state::{
-    farm_profile_dto, points, FarmProfile, NoiseJob, Points, FARM_PROFILES, INFORMATION, NOIS_JOBS,
-    NOIS_JOB_LAST_ID, NOIS_PROXY,
+    farm_profile_dto, listings, load_plant_config, migrate_crop_points, migrate_farm_profiles,
+    migrate_nois_jobs, migrate_watered_plants, points, save_default_plant_types, sprinkler_area,
+    validate_slot, FarmProfile, Points, ALL_TIME_PRIZE_PAYOUTS, CLOCK_MODE, CROP_EVENTS_CONFIG,
+    CROP_TOKENS, EXPANSION_CONFIG, FARMS, INFORMATION, MARKET_CONFIG, NOIS_CONFIG, NOIS_JOBS,
+    NOIS_JOB_LAST_ID, NOIS_PROXY, PENDING_ADMIN, PLANT_TYPES, PRIZE_CONFIG, PRIZE_PAYOUTS,
+    PRIZE_POOL, SEED_PRICES,
 };
 
 const CONTRACT_NAME: &str = "crates.io:farm_template";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Last version storing Nois jobs, crop points and farms in their legacy layout.
+const LEGACY_STORAGE_VERSION: &str = "0.1.0";
+
+fn default_expansion_config() -> ExpansionConfig {
+    ExpansionConfig {
+        step: params::EXPANSION_STEP,
+        max_size: params::EXPANSION_MAX_SIZE,
+        price: None,
+        points_price: Some(params::EXPANSION_POINTS_PRICE),
+    }
+}
+
+fn validate_expansion_config(config: &ExpansionConfig) -> Result<(), ContractError> {
+    if config.step == 0 {
+        return Err(ContractError::InvalidExpansionConfig {
+            reason: "step must be greater than zero".to_string(),
+        });
+    }
+
+    if config.price.is_none() && config.points_price.is_none() {
+        return Err(ContractError::InvalidExpansionConfig {
+            reason: "at least one price must be set".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn expand_farm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payment: PaymentMethod,
+) -> Result<Response, ContractError> {
+    let sender = info.sender.to_string();
+    let mut farm = load_farm(deps.storage, &sender, &[])?;
+
+    let config = EXPANSION_CONFIG.load(deps.storage)?;
+    let mut messages = vec![];
+    let size = farm.get_size();
+    let max_size: usize = config.max_size.into();
+    if size >= max_size {
+        return Err(ContractError::FarmMaxSize {
+            max_size: config.max_size,
+        });
+    }
+
+    match payment {
+        PaymentMethod::Funds => {
+            let price = config.price.ok_or(ContractError::PaymentNotAccepted {})?;
+            if info.funds != [price.clone()] {
+                return Err(ContractError::InvalidFunds {
+                    required: Some(price),
+                });
+            }
 
-fn next_job_id(store: &mut dyn Storage) -> StdResult<String> {
-    let last_id = (NOIS_JOB_LAST_ID.may_load(store)?).unwrap_or(0);
-    let next_id = last_id + 1;
-    NOIS_JOB_LAST_ID.save(store, &next_id)?;
+            messages.push(BankMsg::Send {
+                to_address: treasury(deps.storage)?,
+                amount: info.funds,
+            });
+        }
+        PaymentMethod::Points => {
+            if !info.funds.is_empty() {
+                return Err(ContractError::InvalidFunds { required: None });
+            }
+            let price = config
+                .points_price
+                .ok_or(ContractError::PaymentNotAccepted {})?;
+            let mut pts = points()
+                .may_load(deps.storage, sender.as_str())?
+                .unwrap_or_else(|| Points::new(sender.clone()));
+            pts.spend(price)?;
+            points().save(deps.storage, sender.as_str(), &pts)?;
+        }
+    }
+
+    let amount = usize::from(config.step).min(max_size - size);
+    let now = farm.now(&env.block);
+    farm.upgrade_size(amount, now);
+    farm.save(deps.storage, &sender)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "expand_farm")
+        .add_attribute("size", format!("{0}x{0}", farm.get_size()))
+        .add_messages(messages))
+}
+
+fn default_crop_events_config() -> CropEventsConfig {
+    CropEventsConfig {
+        cooldown: params::CROP_EVENTS_COOLDOWN,
+        drought: params::DROUGHT_CHANCE,
+        pests: params::PESTS_CHANCE,
+        bumper_crop: params::BUMPER_CROP_CHANCE,
+        golden_mutation: params::GOLDEN_MUTATION_CHANCE,
+    }
+}
+
+fn validate_contract_information(
+    api: &dyn Api,
+    information: &ContractInformation,
+) -> Result<(), ContractError> {
+    api.addr_validate(&information.admin)?;
+    for addr in [&information.komple_mint_addr, &information.treasury]
+        .into_iter()
+        .flatten()
+    {
+        api.addr_validate(addr)?;
+    }
 
-    Ok(next_id.to_string())
+    Ok(())
 }
 
-fn mint_seeds(
-    plant: KomplePlant,
-    recipient: String,
-    seeds: i32,
-    storage: &dyn Storage,
-) -> Result<Vec<CosmosMsg>, ContractError> {
-    let information = INFORMATION.load(storage)?;
-    let admin_mint_nft = match information.komple_mint_addr {
-        None => Err(throw_err("Komple mint addr missing.")),
-        Some(komple_mint_addr) => Ok(WasmMsg::Execute {
-            contract_addr: komple_mint_addr,
-            msg: to_binary::<KompleMintExecuteMsg>(&KompleMintExecuteMsg::AdminMint {
-                collection_id: plant.collection_id,
-                recipient,
-                metadata_id: Some(plant.metadata_id),
-            })?,
-            funds: vec![],
-        }),
-    }?;
-
-    let mut messages: Vec<CosmosMsg> = vec![];
-    for _i in 0..seeds {
-        messages.push(admin_mint_nft.clone().into());
+fn validate_nois_config(config: &NoisConfig) -> Result<(), ContractError> {
+    if config.job_timeout == 0 || config.job_timeout > params::MAX_NOIS_JOB_TIMEOUT {
+        return Err(ContractError::InvalidNoisConfig {
+            reason: format!(
+                "job_timeout must be between 1 and {}",
+                params::MAX_NOIS_JOB_TIMEOUT
+            ),
+        });
     }
 
-    Ok(messages)
+    Ok(())
 }
 
-fn noise_job(
-    noise_job: NoiseJob,
-    randomness: [u8; 32],
-    storage: &dyn Storage,
-) -> Result<Vec<CosmosMsg>, ContractError> {
-    match noise_job {
-        NoiseJob::MintSeeds { plant, recipient } => {
-            let seeds = int_in_range(randomness, 2, 5);
+fn validate_crop_events_config(config: &CropEventsConfig) -> Result<(), ContractError> {
+    let chances = [
+        config.drought,
+        config.pests,
+        config.bumper_crop,
+        config.golden_mutation,
+    ];
+
+    if chances.iter().any(|chance| *chance > 100) {
+        return Err(ContractError::InvalidCropEventsConfig {
+            reason: "chances must be between 0 and 100".to_string(),
+        });
+    }
 
-            mint_seeds(plant, recipient, seeds, storage)
-        }
+    if config.cooldown > params::MAX_CROP_EVENTS_COOLDOWN {
+        return Err(ContractError::InvalidCropEventsConfig {
+            reason: format!(
+                "cooldown must not exceed {}",
+                params::MAX_CROP_EVENTS_COOLDOWN
+            ),
+        });
     }
+
+    Ok(())
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -83,18 +203,32 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    let admin = msg.admin.unwrap_or_else(|| info.sender.into_string());
-    deps.api.addr_validate(&admin)?;
+    let information = ContractInformation {
+        admin: msg.admin.unwrap_or_else(|| info.sender.into_string()),
+        komple_mint_addr: msg.komple_mint_addr,
+        treasury: None,
+    };
+    validate_contract_information(deps.api, &information)?;
+    INFORMATION.save(deps.storage, &information)?;
 
-    INFORMATION.save(
+    NOIS_JOB_LAST_ID.save(deps.storage, &0)?;
+    NOIS_CONFIG.save(
         deps.storage,
-        &ContractInformation {
-            admin,
-            komple_mint_addr: msg.komple_mint_addr,
+        &NoisConfig {
+            job_timeout: params::NOIS_JOB_TIMEOUT,
         },
     )?;
-
-    NOIS_JOB_LAST_ID.save(deps.storage, &0)?;
+    EXPANSION_CONFIG.save(deps.storage, &default_expansion_config())?;
+    MARKET_CONFIG.save(
+        deps.storage,
+        &MarketConfig {
+            fee_bps: params::MARKET_FEE_BPS,
+        },
+    )?;
+    CROP_EVENTS_CONFIG.save(deps.storage, &default_crop_events_config())?;
+    let clock = msg.clock_mode.unwrap_or_default();
+    CLOCK_MODE.save(deps.storage, &clock)?;
+    save_default_plant_types(deps.storage, &clock)?;
 
     match msg.nois_proxy {
         None => (),
@@ -107,10 +241,62 @@ pub fn instantiate(
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
+fn parse_version(version: &str) -> StdResult<Version> {
+    Version::parse(version).map_err(|err| StdError::generic_err(err.to_string()))
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // The legacy data is converted once, later migrations don't have to scan every farm.
+    let stored = get_contract_version(deps.storage)?;
+    let legacy_storage = parse_version(&stored.version)? <= parse_version(LEGACY_STORAGE_VERSION)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
+    if NOIS_CONFIG.may_load(deps.storage)?.is_none() {
+        NOIS_CONFIG.save(
+            deps.storage,
+            &NoisConfig {
+                job_timeout: params::NOIS_JOB_TIMEOUT,
+            },
+        )?;
+    }
+
+    // Farms created before the clock mode existed count blocks.
+    let clock = match CLOCK_MODE.may_load(deps.storage)? {
+        Some(clock) => clock,
+        None => {
+            CLOCK_MODE.save(deps.storage, &ClockMode::BlockHeight)?;
+            ClockMode::BlockHeight
+        }
+    };
+    if legacy_storage {
+        migrate_nois_jobs(deps.storage, env.block.height)?;
+        migrate_crop_points(deps.storage)?;
+        migrate_farm_profiles(deps.storage)?;
+        migrate_watered_plants(deps.storage)?;
+    }
+
+    if EXPANSION_CONFIG.may_load(deps.storage)?.is_none() {
+        EXPANSION_CONFIG.save(deps.storage, &default_expansion_config())?;
+    }
+
+    if MARKET_CONFIG.may_load(deps.storage)?.is_none() {
+        MARKET_CONFIG.save(
+            deps.storage,
+            &MarketConfig {
+                fee_bps: params::MARKET_FEE_BPS,
+            },
+        )?;
+    }
+
+    if CROP_EVENTS_CONFIG.may_load(deps.storage)?.is_none() {
+        CROP_EVENTS_CONFIG.save(deps.storage, &default_crop_events_config())?;
+    }
+
+    if PLANT_TYPES.is_empty(deps.storage) {
+        save_default_plant_types(deps.storage, &clock)?;
+    }
+
     Ok(Response::default())
 }
 
@@ -121,180 +307,299 @@ pub fn execute(
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
+    ensure_not_paused(deps.storage, &msg)?;
+
     match msg {
         ExecuteMsg::Start {} => {
             let sender = info.sender.to_string();
 
-            if FARM_PROFILES
-                .may_load(deps.storage, sender.as_str())?
-                .is_some()
-            {
-                return Err(throw_err("Farm already exists for you"));
+            if FARMS.has(deps.storage, sender.as_str()) {
+                return Err(ContractError::FarmAlreadyExists { address: sender });
             }
 
-            let farm_profile: FarmProfile = FarmProfile::new(env.block.height);
-            FARM_PROFILES.save(deps.storage, sender.as_str(), &farm_profile)?;
+            let clock = CLOCK_MODE.load(deps.storage)?;
+            let farm_profile: FarmProfile = FarmProfile::new(clock.now(&env.block), clock);
+            farm_profile.save(deps.storage, &sender)?;
 
             Ok(Response::new().add_attribute("action", "start"))
         }
 
         ExecuteMsg::SetupFarm { farm, addr } => {
-            let sender = info.sender.to_string();
-            let info = INFORMATION.load(deps.storage)?;
+            role_error_check(deps.as_ref(), info, Role::GameMaster)?;
+
+            let clock = CLOCK_MODE.load(deps.storage)?;
+            farm.validate(deps.storage, clock.now(&env.block))?;
+
+            // The seeds of the replaced plants go back to the owner, as on `Stop`.
+            let refunds = match FarmProfile::load_all(deps.storage, addr.as_str())? {
+                None => vec![],
+                Some(existing) => existing
+                    .seed_nfts()
+                    .iter()
+                    .map(|(_, _, nft)| transfer_nft_msg(nft, addr.to_string()))
+                    .collect::<StdResult<Vec<_>>>()?,
+            };
+            FarmProfile::remove(deps.storage, addr.as_str())?;
+            FarmProfile::from_layout(farm, clock).save(deps.storage, addr.as_str())?;
+
+            Ok(Response::new()
+                .add_attribute("action", "setup_farm")
+                .add_messages(refunds))
+        }
+
+        ExecuteMsg::PatchPlots { addr, patches } => {
+            role_error_check(deps.as_ref(), info, Role::GameMaster)?;
 
-            if sender != info.admin {
-                return Err(ContractError::Unauthorized {});
+            if patches.is_empty() {
+                return Err(ContractError::InvalidFarm {
+                    reason: "no plots to patch".to_string(),
+                });
             }
 
-            FARM_PROFILES.save(deps.storage, addr.as_str(), &farm)?;
+            let area: Vec<(u8, u8)> = patches.iter().map(|(x, y, _)| (*x, *y)).collect();
+            let mut farm = load_farm(deps.storage, addr.as_str(), &area)?;
+            let now = farm.now(&env.block);
+
+            let mut refunds = vec![];
+            for (x, y, mut slot) in patches {
+                validate_slot(deps.storage, &slot, x.into(), y.into(), now)?;
+                if let Some(plant) = slot.plant.as_mut() {
+                    plant.compact_watering();
+                }
+
+                let replaced = farm.get_plot(x.into(), y.into())?;
+                if let Some(nft) = replaced.plant.and_then(|plant| plant.seed_nft) {
+                    refunds.push(transfer_nft_msg(&nft, addr.to_string())?);
+                }
+                farm.set_plot(x.into(), y.into(), slot)?;
+            }
+            farm.save(deps.storage, addr.as_str())?;
 
-            Ok(Response::new().add_attribute("action", "setup_farm"))
+            Ok(Response::new()
+                .add_attribute("action", "patch_plots")
+                .add_attribute("plots", area.len().to_string())
+                .add_messages(refunds))
         }
 
         ExecuteMsg::Stop {} => {
             let sender = info.sender.to_string();
-            FARM_PROFILES.remove(deps.storage, sender.as_str());
-
-            Ok(Response::new().add_attribute("action", "stop"))
+            let refunds = match FarmProfile::load_all(deps.storage, &sender)? {
+                None => vec![],
+                Some(farm) => farm
+                    .seed_nfts()
+                    .iter()
+                    .map(|(_, _, nft)| transfer_nft_msg(nft, sender.clone()))
+                    .collect::<StdResult<Vec<_>>>()?,
+            };
+            FarmProfile::remove(deps.storage, &sender)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "stop")
+                .add_messages(refunds))
         }
 
         ExecuteMsg::TillGround { x, y } => {
             let sender = info.sender.to_string();
-            let farm = FARM_PROFILES.may_load(deps.storage, sender.as_str())?;
+            let mut farm = load_farm(deps.storage, &sender, &[(x, y)])?;
 
-            match farm {
-                None => Err(throw_err("You do not have a farm")),
-                Some(mut farm) => {
-                    farm.till(x.into(), y.into(), env.block.height)?;
-                    FARM_PROFILES.save(deps.storage, sender.as_str(), &farm)?;
+            let now = farm.now(&env.block);
+            let messages = till_plot(&mut farm, &sender, x, y, now)?;
+            farm.save(deps.storage, &sender)?;
 
-                    Ok(Response::new().add_attribute("action", "tilled"))
-                }
-            }
+            Ok(Response::new()
+                .add_attribute("action", "tilled")
+                .add_messages(messages))
+        }
+
+        ExecuteMsg::BuildSprinkler { x, y } => {
+            let sender = info.sender.to_string();
+            let mut farm = load_farm(deps.storage, &sender, &sprinkler_area(x, y))?;
+
+            let now = farm.now(&env.block);
+            farm.build_sprinkler(x.into(), y.into(), now)?;
+
+            let mut pts = points()
+                .may_load(deps.storage, sender.as_str())?
+                .unwrap_or_else(|| Points::new(sender.clone()));
+            pts.spend(params::SPRINKLER_POINTS_PRICE)?;
+
+            farm.save(deps.storage, &sender)?;
+            points().save(deps.storage, sender.as_str(), &pts)?;
+
+            Ok(Response::new().add_attribute("action", "build_sprinkler"))
+        }
+
+        ExecuteMsg::RemoveSprinkler { x, y } => {
+            let sender = info.sender.to_string();
+            let mut farm = load_farm(deps.storage, &sender, &sprinkler_area(x, y))?;
+
+            let now = farm.now(&env.block);
+            farm.remove_sprinkler(x.into(), y.into(), now)?;
+            farm.save(deps.storage, &sender)?;
+
+            Ok(Response::new().add_attribute("action", "remove_sprinkler"))
         }
 
         ExecuteMsg::ReceiveNft(msg) => receive(deps, env, info, msg),
 
-        ExecuteMsg::NoisReceive { callback } => {
-            let proxy = NOIS_PROXY.load(deps.storage)?;
-            ensure_eq!(info.sender, proxy, throw_err("Unauthorized sender"));
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
 
-            let NoisCallback {
-                job_id, randomness, ..
-            } = callback;
+        ExecuteMsg::BuyListing { listing_id } => buy_listing(deps, info, listing_id),
 
-            let randomness: [u8; 32] = randomness
-                .to_array()
-                .map_err(|_| throw_err("Invalid randomness"))?;
+        ExecuteMsg::CancelListing { listing_id } => cancel_listing(deps, info, listing_id),
 
-            let job = NOIS_JOBS.load(deps.storage, &job_id)?;
-            NOIS_JOBS.remove(deps.storage, &job_id);
-            let messages = noise_job(job, randomness, deps.storage)?;
+        ExecuteMsg::UpdateMarketConfig { config } => update_market_config(deps, info, config),
 
-            Ok(Response::new().add_messages(messages))
+        ExecuteMsg::NoisReceive { callback } => nois_receive(deps, env, info, callback),
+
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, info, paused),
+
+        ExecuteMsg::SetActionPaused { action, paused } => {
+            set_action_paused(deps, info, action, paused)
         }
 
         ExecuteMsg::WaterPlant { x, y } => {
             let sender = info.sender.to_string();
-            let farm: Option<FarmProfile> =
-                FARM_PROFILES.may_load(deps.storage, sender.as_str())?;
+            let mut farm = load_farm(deps.storage, &sender, &[(x, y)])?;
 
-            match farm {
-                None => Err(throw_err("You do not have a farm")),
-                Some(mut farm) => {
-                    farm.water_plant(x.into(), y.into(), env.block.height)?;
-                    FARM_PROFILES.save(deps.storage, sender.as_str(), &farm)?;
+            let now = farm.now(&env.block);
+            farm.water_plant(x.into(), y.into(), now)?;
+            farm.save(deps.storage, &sender)?;
 
-                    Ok(Response::new().add_attribute("action", "watered"))
-                }
-            }
+            Ok(Response::new().add_attribute("action", "watered"))
         }
 
         ExecuteMsg::Harvest { x, y } => {
             let sender = info.sender.to_string();
-            let farm = FARM_PROFILES.may_load(deps.storage, sender.as_str())?;
-
-            match farm {
-                None => Err(throw_err("You do not have a farm")),
-                Some(mut farm) => {
-                    let plot = farm.get_plot(x.into(), y.into());
-                    let plant = plot.plant;
-
-                    match plant {
-                        None => Err(throw_err(&format!(
-                            "Plot [{}, {}] must contain a plant to harvest.",
-                            x, y
-                        ))),
-                        Some(plant) => {
-                            let messages = match plant.komple {
-                                None => Err(throw_err("Plant komple missing.")),
-                                Some(komple) => {
-                                    let nois_proxy = NOIS_PROXY.may_load(deps.storage)?;
-                                    match nois_proxy {
-                                        None => mint_seeds(
-                                            komple,
-                                            info.sender.into_string(),
-                                            2,
-                                            deps.storage,
-                                        ),
-                                        Some(nois_proxy) => {
-                                            let job = NoiseJob::MintSeeds {
-                                                plant: komple,
-                                                recipient: info.sender.into_string(),
-                                            };
-                                            let job_id = next_job_id(deps.storage)?;
-                                            NOIS_JOBS.save(deps.storage, &job_id, &job)?;
-
-                                            let mut messages: Vec<CosmosMsg> = vec![];
-                                            let msg = WasmMsg::Execute {
-                                                contract_addr: nois_proxy.into(),
-                                                msg: to_binary(
-                                                    &ProxyExecuteMsg::GetNextRandomness { job_id },
-                                                )?,
-                                                funds: info.funds.clone(),
-                                            };
-                                            messages.push(msg.into());
-                                            Ok(messages)
-                                        }
-                                    }
-                                }
-                            }?;
-
-                            let harvested = farm.harvest(x.into(), y.into(), env.block.height)?;
-                            let mut pts = match points().may_load(deps.storage, sender.as_str())? {
-                                None => Points {
-                                    addr: sender.clone(),
-                                    plants: HashMap::new(),
-                                },
-                                Some(p) => p,
-                            };
-                            pts.add(harvested);
-
-                            FARM_PROFILES.save(deps.storage, sender.as_str(), &farm)?;
-                            points().save(deps.storage, sender.as_str(), &pts)?;
-
-                            Ok(Response::new()
-                                .add_attribute("action", "harvested")
-                                .add_messages(messages))
-                        }
-                    }
-                }
-            }
+            let mut farm = load_farm(deps.storage, &sender, &[(x, y)])?;
+
+            let messages = harvest_plot(
+                deps.storage,
+                &mut farm,
+                &sender,
+                x,
+                y,
+                &env.block,
+                info.funds,
+            )?;
+            farm.save(deps.storage, &sender)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "harvested")
+                .add_messages(messages))
         }
+
+        ExecuteMsg::Batch {
+            actions,
+            skip_failures,
+        } => execute_batch(deps, env, info, actions, skip_failures),
+
         ExecuteMsg::UpdateContractInformation {
             contract_information,
         } => {
-            let sender = info.sender.to_string();
-            let info = INFORMATION.load(deps.storage)?;
+            admin_error_check(deps.as_ref(), info)?;
 
-            if sender != info.admin {
-                return Err(ContractError::Unauthorized {});
+            let current = INFORMATION.load(deps.storage)?;
+            if contract_information.admin != current.admin {
+                return Err(ContractError::InvalidContractInformation {
+                    reason: "the admin can only change through ProposeAdmin".to_string(),
+                });
             }
+            validate_contract_information(deps.api, &contract_information)?;
 
             INFORMATION.save(deps.storage, &contract_information)?;
 
             Ok(Response::new().add_attribute("action", "update_contract_information"))
         }
+
+        ExecuteMsg::ProposeAdmin { admin } => propose_admin(deps, info, admin),
+
+        ExecuteMsg::AcceptAdmin {} => accept_admin(deps, info),
+
+        ExecuteMsg::GrantRole { address, role } => grant_role(deps, info, address, role),
+
+        ExecuteMsg::RevokeRole { address, role } => revoke_role(deps, info, address, role),
+
+        ExecuteMsg::ExpandFarm { payment } => expand_farm(deps, env, info, payment),
+
+        ExecuteMsg::UpdateExpansionConfig { config } => {
+            role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+            validate_expansion_config(&config)?;
+
+            EXPANSION_CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::new().add_attribute("action", "update_expansion_config"))
+        }
+
+        ExecuteMsg::UpdateNoisConfig { config } => {
+            admin_error_check(deps.as_ref(), info)?;
+            validate_nois_config(&config)?;
+            NOIS_CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::new().add_attribute("action", "update_nois_config"))
+        }
+
+        ExecuteMsg::UpdateCropEventsConfig { config } => {
+            admin_error_check(deps.as_ref(), info)?;
+            validate_crop_events_config(&config)?;
+
+            CROP_EVENTS_CONFIG.save(deps.storage, &config)?;
+
+            Ok(Response::new().add_attribute("action", "update_crop_events_config"))
+        }
+
+        ExecuteMsg::RollCropEvents {} => roll_crop_events(deps, env, info),
+
+        ExecuteMsg::CreateSeason { start, end } => create_season(deps, env, info, start, end),
+
+        ExecuteMsg::CloseSeason { season_id } => close_season(deps, env, season_id),
+
+        ExecuteMsg::UpdatePrizeConfig { config } => update_prize_config(deps, info, config),
+
+        ExecuteMsg::FundPrizePool {} => fund_prize_pool(deps, info),
+
+        ExecuteMsg::DistributePrizes { season_id } => distribute_prizes(deps, info, season_id),
+
+        ExecuteMsg::SettleNoisJob { job_id } => settle_job(deps, env, job_id),
+
+        ExecuteMsg::RetryNoisJob { job_id } => retry_job(deps, env, info, job_id),
+
+        ExecuteMsg::SetSeedPrice { plant_type, price } => {
+            set_seed_price(deps, info, plant_type, price)
+        }
+
+        ExecuteMsg::BuySeeds { plant_type, amount } => buy_seeds(deps, info, plant_type, amount),
+
+        ExecuteMsg::SetCropToken { plant_type, token } => {
+            role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+            let plant_type: PlantType = plant_type.parse()?;
+            load_plant_config(deps.storage, &plant_type)?;
+
+            match &token {
+                None => CROP_TOKENS.remove(deps.storage, plant_type.as_str()),
+                Some(token) => {
+                    let token = deps.api.addr_validate(token)?;
+                    CROP_TOKENS.save(deps.storage, plant_type.as_str(), &token)?;
+                }
+            }
+
+            Ok(Response::new()
+                .add_attribute("action", "set_crop_token")
+                .add_attribute("plant_type", plant_type.to_string())
+                .add_attribute("token", token.unwrap_or_default()))
+        }
+
+        ExecuteMsg::SetPlantType { name, config } => {
+            role_error_check(deps.as_ref(), info, Role::GameMaster)?;
+            let plant_type: PlantType = name.parse()?;
+            config.validate()?;
+
+            PLANT_TYPES.save(deps.storage, plant_type.as_str(), &config)?;
+
+            Ok(Response::new()
+                .add_attribute("action", "set_plant_type")
+                .add_attribute("plant_type", plant_type.to_string()))
+        }
     }
 }
 
@@ -307,33 +612,179 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             Ok(v)
         }
         QueryMsg::GetFarmProfile { address } => {
-            let farm = FARM_PROFILES.may_load(deps.storage, address.as_str())?;
-            let farm_dto = farm_profile_dto(&farm, env.block.height);
+            let farm = FarmProfile::load_all(deps.storage, &address)?;
+            let farm_dto = farm
+                .as_ref()
+                .map(|farm| farm_profile_dto(farm, farm.now(&env.block)));
 
             let v = to_binary(&farm_dto)?;
             Ok(v)
         }
-        QueryMsg::Leaderboard {} => {
-            let res: Result<Vec<(String, u64)>, _> = points()
-                .idx
-                .total
-                .range(deps.storage, None, None, Order::Descending)
-                .take(100)
-                .map(|res| match res {
-                    Ok((_, v)) => {
-                        let total: u64 = v.total();
-                        let addr: String = v.addr;
-
-                        Ok((addr, total))
-                    }
-                    Err(err) => Err(err),
+        QueryMsg::Leaderboard {
+            plant_type,
+            start_after,
+            limit,
+        } => to_binary(&query_leaderboard(
+            deps.storage,
+            plant_type,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Rank {
+            address,
+            plant_type,
+        } => to_binary(&query_rank(deps.storage, address, plant_type)?),
+        QueryMsg::Seasons { start_after, limit } => {
+            to_binary(&query_seasons(deps.storage, start_after, limit)?)
+        }
+        QueryMsg::Season { season_id } => {
+            let season = load_season(deps.storage, season_id)
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            to_binary(&season)
+        }
+        QueryMsg::CurrentSeason {} => to_binary(&active_season(deps.storage, env.block.height)?),
+        QueryMsg::SeasonLeaderboard {
+            season_id,
+            start_after,
+            limit,
+        } => to_binary(&query_season_leaderboard(
+            deps.storage,
+            season_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SeasonResults {
+            season_id,
+            start_after,
+            limit,
+        } => to_binary(&query_season_results(
+            deps.storage,
+            season_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::PrizeConfig {} => to_binary(&PRIZE_CONFIG.may_load(deps.storage)?),
+        QueryMsg::PrizePool {} => {
+            to_binary(&PRIZE_POOL.may_load(deps.storage)?.unwrap_or_default())
+        }
+        QueryMsg::PrizePayouts { season_id } => {
+            let payouts = match season_id {
+                Some(season_id) => PRIZE_PAYOUTS.may_load(deps.storage, season_id)?,
+                None => ALL_TIME_PRIZE_PAYOUTS.may_load(deps.storage)?,
+            };
+            to_binary(&payouts.unwrap_or_default())
+        }
+        QueryMsg::FarmNfts { address } => {
+            let farm = FarmProfile::load_all(deps.storage, &address)?;
+            let nfts: Vec<FarmNft> = farm
+                .map(|farm| farm.seed_nfts())
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(x, y, nft)| FarmNft {
+                    x: x as u8,
+                    y: y as u8,
+                    nft,
                 })
                 .collect();
 
-            match res {
-                Ok(v) => Ok(to_binary(&v)?),
-                Err(err) => Err(err),
-            }
+            to_binary(&nfts)
+        }
+        QueryMsg::ClockMode {} => to_binary(&CLOCK_MODE.load(deps.storage)?),
+        QueryMsg::ExpansionConfig {} => {
+            let config = EXPANSION_CONFIG.load(deps.storage)?;
+            let v = to_binary(&config)?;
+            Ok(v)
+        }
+        QueryMsg::NoisConfig {} => {
+            let config = NOIS_CONFIG.load(deps.storage)?;
+            let v = to_binary(&config)?;
+            Ok(v)
+        }
+        QueryMsg::CropEventsConfig {} => {
+            let config = CROP_EVENTS_CONFIG.load(deps.storage)?;
+            let v = to_binary(&config)?;
+            Ok(v)
+        }
+        QueryMsg::PendingNoisJobs { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(params::DEFAULT_LIMIT)
+                .min(params::MAX_LIMIT) as usize;
+            let start = start_after.as_deref().map(Bound::exclusive);
+
+            let jobs: StdResult<Vec<PendingNoisJobResponse>> = NOIS_JOBS
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|res| res.map(|(job_id, job)| PendingNoisJobResponse { job_id, job }))
+                .collect();
+
+            to_binary(&jobs?)
+        }
+        QueryMsg::PlantTypes { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(params::DEFAULT_LIMIT)
+                .min(params::MAX_LIMIT) as usize;
+            let start = start_after.as_deref().map(Bound::exclusive);
+
+            let plant_types: StdResult<Vec<PlantTypeResponse>> = PLANT_TYPES
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|res| res.map(|(name, config)| PlantTypeResponse { name, config }))
+                .collect();
+
+            to_binary(&plant_types?)
+        }
+        QueryMsg::PlantType { name } => {
+            let plant_type: PlantType = name
+                .parse()
+                .map_err(|err: ContractError| StdError::generic_err(err.to_string()))?;
+            let config = PLANT_TYPES.load(deps.storage, plant_type.as_str())?;
+            let v = to_binary(&config)?;
+            Ok(v)
+        }
+        QueryMsg::SeedPrices { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(params::DEFAULT_LIMIT)
+                .min(params::MAX_LIMIT) as usize;
+            let start = start_after.as_deref().map(Bound::exclusive);
+
+            let prices: StdResult<Vec<SeedPriceResponse>> = SEED_PRICES
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|res| res.map(|(plant_type, price)| SeedPriceResponse { plant_type, price }))
+                .collect();
+
+            to_binary(&prices?)
+        }
+        QueryMsg::Listings {
+            seller,
+            plant_type,
+            start_after,
+            limit,
+        } => to_binary(&query_listings(
+            deps,
+            seller,
+            plant_type,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Listing { listing_id } => to_binary(&listings().load(deps.storage, listing_id)?),
+        QueryMsg::MarketConfig {} => to_binary(&MARKET_CONFIG.load(deps.storage)?),
+        QueryMsg::PauseStatus {} => to_binary(&load_pause_status(deps.storage)?),
+        QueryMsg::PendingAdmin {} => to_binary(&PENDING_ADMIN.may_load(deps.storage)?),
+        QueryMsg::Roles { address } => to_binary(&query_roles(deps, address)?),
+        QueryMsg::CropTokens { start_after, limit } => {
+            let limit = limit
+                .unwrap_or(params::DEFAULT_LIMIT)
+                .min(params::MAX_LIMIT) as usize;
+            let start = start_after.as_deref().map(Bound::exclusive);
+
+            let tokens: StdResult<Vec<CropTokenResponse>> = CROP_TOKENS
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(limit)
+                .map(|res| res.map(|(plant_type, token)| CropTokenResponse { plant_type, token }))
+                .collect();
+
+            to_binary(&tokens?)
         }
     }
 }
@@ -342,19 +793,14 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
 mod tests {
     use cosmwasm_std::testing::mock_dependencies;
 
-    use crate::farm::PlantType;
-
     use super::*;
 
     #[test]
     fn save_and_load_points() {
         let mut deps = mock_dependencies();
 
-        let mut new_points = Points {
-            addr: "123".into(),
-            plants: HashMap::new(),
-        };
-        new_points.add(PlantType::Sunflower);
+        let mut new_points = Points::new("123".into());
+        new_points.add(&"sunflower".parse().unwrap(), 1);
 
         let pts = points();
         pts.save(deps.as_mut().storage, "123", &new_points).unwrap();
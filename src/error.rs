@@ -1,38 +1,240 @@
-use cosmwasm_std::StdError;
+use std::fmt;
+
+use cosmwasm_std::{Coin, OverflowError, StdError};
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+use crate::msg::PausableAction;
+
+#[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
     Std(#[from] StdError),
 
+    #[error("{0}")]
+    Overflow(#[from] OverflowError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
-    // Add any other custom errors you like here.
-    // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
-    #[error("Player account already exist: {address}")]
-    PlayerAlreadyExists { address: String },
+    #[error("Farm does not exist: {address}")]
+    NoFarm { address: String },
+
+    #[error("Farm already exists: {address}")]
+    FarmAlreadyExists { address: String },
 
-    #[error("Player does not exist: {address}")]
-    PlayerDoesNotExist { address: String },
+    #[error("Plot [{x}, {y}] is out of bounds for farm of size {size}x{size}")]
+    PlotOutOfBounds { x: usize, y: usize, size: usize },
 
-    #[error("That asset does not exist: {name}")]
-    AssetDoesNotExist { name: String },
+    #[error("Plot [{x}, {y}] must be meadow or field with dead plant to till")]
+    PlotNotTillable { x: usize, y: usize },
 
-    #[error("You have not yet purchased this asset: {name}")]
-    AssetNotPurchased { name: String },
+    #[error("Plot [{x}, {y}] must be a field")]
+    PlotNotField { x: usize, y: usize },
 
-    #[error("You have already bought this asset: {name}")]
-    AssetAlreadyPurchased { name: String },
+    #[error("Plot [{x}, {y}] must be a meadow")]
+    PlotNotMeadow { x: usize, y: usize },
 
-    #[error("Uknown plant: {name}")]
+    #[error("Plot [{x}, {y}] does not contain a sprinkler")]
+    NoSprinkler { x: usize, y: usize },
+
+    #[error("Plot [{x}, {y}] already contains a plant")]
+    PlotOccupied { x: usize, y: usize },
+
+    #[error("Plot [{x}, {y}] does not contain a plant")]
+    NoPlant { x: usize, y: usize },
+
+    #[error("Plant [{x}, {y}] cannot be watered: {reason}")]
+    PlantNotWaterable {
+        x: usize,
+        y: usize,
+        reason: WaterBlockReason,
+    },
+
+    #[error("Plant [{x}, {y}] cannot be fertilized: {reason}")]
+    PlantNotFertilizable {
+        x: usize,
+        y: usize,
+        reason: FertilizeBlockReason,
+    },
+
+    #[error("Unknown fertilizer: {name}")]
+    UnknownFertilizer { name: String },
+
+    #[error("Plant [{x}, {y}] is dead")]
+    PlantDead { x: usize, y: usize },
+
+    #[error("Plant [{x}, {y}] must be fully grown and watered to harvest it")]
+    NotHarvestable { x: usize, y: usize },
+
+    #[error("Plant [{x}, {y}] was not planted from a Komple seed")]
+    PlantKompleMissing { x: usize, y: usize },
+
+    #[error("Unknown plant: {name}")]
     UnknownPlant { name: String },
 
+    #[error("Invalid plant config: {reason}")]
+    InvalidPlantConfig { reason: String },
+
+    #[error("Nois proxy is not configured")]
+    NoisProxyMissing {},
+
+    #[error("Nois job does not exist: {job_id}")]
+    UnknownNoisJob { job_id: String },
+
+    #[error("Nois job {job_id} can not be settled before block {expires_at}")]
+    NoisJobNotExpired { job_id: String, expires_at: u64 },
+
+    #[error("Crop events can be rolled again at block {available_at}")]
+    CropEventsCooldown { available_at: u64 },
+
+    #[error("Invalid crop events config: {reason}")]
+    InvalidCropEventsConfig { reason: String },
+
+    #[error("Invalid Nois config: {reason}")]
+    InvalidNoisConfig { reason: String },
+
+    #[error("Invalid season: {reason}")]
+    InvalidSeason { reason: String },
+
+    #[error("Season {season_id} does not exist")]
+    UnknownSeason { season_id: u64 },
+
+    #[error("Season {season_id} ends at block {end}")]
+    SeasonNotEnded { season_id: u64, end: u64 },
+
+    #[error("Season {season_id} is already closed")]
+    SeasonClosed { season_id: u64 },
+
+    #[error("Prizes are not configured")]
+    PrizeConfigMissing {},
+
+    #[error("Invalid prize config: {reason}")]
+    InvalidPrizeConfig { reason: String },
+
+    #[error("Prize pool accepts only {denom}")]
+    InvalidPrizeFunds { denom: String },
+
+    #[error("Prizes for season {season_id} were already distributed")]
+    PrizesDistributed { season_id: u64 },
+
+    #[error("Prizes for the all time leaderboard were already distributed")]
+    AllTimePrizesDistributed {},
+
+    #[error("No prizes to distribute")]
+    NoPrizes {},
+
+    #[error("Invalid randomness")]
+    InvalidRandomness {},
+
+    #[error("Komple mint address is not configured")]
+    KompleMintMissing {},
+
+    #[error("Unauthorized collection: {address}")]
+    UnauthorizedCollection { address: String },
+
+    #[error("Invalid token id: {token_id}")]
+    InvalidTokenId { token_id: String },
+
+    #[error("Komple metadata submodule is missing")]
+    KompleMetadataMissing {},
+
+    #[error("Metadata attribute is missing: {trait_type}")]
+    MetadataAttributeMissing { trait_type: String },
+
     #[error("You do not have enough points: balance={received:?},required={required:?}. MaxYouCanBuy={max_amount:?}")]
     NotEnoughPoints {
         received: u128,
         required: u128,
         max_amount: Option<u64>,
     },
+
+    #[error("Invalid expansion config: {reason}")]
+    InvalidExpansionConfig { reason: String },
+
+    #[error("Farm already has the maximum size: {max_size}x{max_size}")]
+    FarmMaxSize { max_size: u8 },
+
+    #[error("Payment method is not accepted")]
+    PaymentNotAccepted {},
+
+    #[error("Seeds of {plant_type} are not for sale")]
+    SeedsNotForSale { plant_type: String },
+
+    #[error("Between 1 and {max_amount} seeds can be bought at once")]
+    InvalidSeedAmount { max_amount: u32 },
+
+    #[error("{address} is not a crop token")]
+    UnknownCropToken { address: String },
+
+    #[error("Listing {listing_id} does not exist")]
+    UnknownListing { listing_id: u64 },
+
+    #[error("Price must be greater than zero")]
+    InvalidPrice {},
+
+    #[error("Invalid market config: {reason}")]
+    InvalidMarketConfig { reason: String },
+
+    /// `required` is `None` when the message must not carry any funds.
+    #[error("Invalid funds: required {}", display_required(required))]
+    InvalidFunds { required: Option<Coin> },
+
+    #[error("Invalid batch: {reason}")]
+    InvalidBatch { reason: String },
+
+    #[error("Invalid farm: {reason}")]
+    InvalidFarm { reason: String },
+
+    #[error("The game is paused")]
+    Paused {},
+
+    #[error("{action} is paused")]
+    ActionPaused { action: PausableAction },
+
+    #[error("Invalid contract information: {reason}")]
+    InvalidContractInformation { reason: String },
+
+    #[error("No admin transfer is pending")]
+    NoPendingAdmin {},
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WaterBlockReason {
+    FullyGrown,
+    AlreadyWatered,
+}
+
+fn display_required(required: &Option<Coin>) -> String {
+    match required {
+        Some(coin) => coin.to_string(),
+        None => "no funds".to_string(),
+    }
+}
+
+impl fmt::Display for WaterBlockReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WaterBlockReason::FullyGrown => write!(f, "plant is fully grown"),
+            WaterBlockReason::AlreadyWatered => write!(f, "plant was already watered this stage"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FertilizeBlockReason {
+    FullyGrown,
+    NeedsWater,
+    AlreadyFertilized,
+}
+
+impl fmt::Display for FertilizeBlockReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FertilizeBlockReason::FullyGrown => write!(f, "plant is fully grown"),
+            FertilizeBlockReason::NeedsWater => write!(f, "plant must be watered this stage"),
+            FertilizeBlockReason::AlreadyFertilized => {
+                write!(f, "plant was already fertilized with it")
+            }
+        }
+    }
 }
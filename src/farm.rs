@@ -1,6 +1,7 @@
-use std::str::FromStr;
+use std::{fmt, str::FromStr};
 
 use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{BlockInfo, Uint128};
 
 use crate::{params, ContractError};
 
@@ -8,34 +9,158 @@ use crate::{params, ContractError};
 pub enum SlotType {
     Meadow,
     Field,
+    /// Irrigates the plants on the neighbouring plots.
+    Sprinkler,
 }
 
+/// Unit of the farm timers, chosen at instantiation.
+#[cw_serde]
+#[derive(Default)]
+pub enum ClockMode {
+    #[default]
+    BlockHeight,
+    /// Seconds of block time, growth speed does not depend on the block time of the chain.
+    Seconds,
+}
+
+impl ClockMode {
+    pub fn now(&self, block: &BlockInfo) -> u64 {
+        match self {
+            ClockMode::BlockHeight => block.height,
+            ClockMode::Seconds => block.time.seconds(),
+        }
+    }
+
+    /// Converts a default duration given in blocks to the unit of the clock.
+    pub fn duration(&self, blocks: u64) -> u64 {
+        match self {
+            ClockMode::BlockHeight => blocks,
+            ClockMode::Seconds => blocks.saturating_mul(params::BLOCK_TIME_SECONDS),
+        }
+    }
+}
+
+/// Name of a crop registered in `PLANT_TYPES`.
 #[cw_serde]
 #[derive(Eq, Hash)]
-pub enum PlantType {
-    Sunflower,
-    Wheat,
+pub struct PlantType(String);
+
+impl PlantType {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
 }
 
 impl FromStr for PlantType {
     type Err = ContractError;
 
     fn from_str(s: &str) -> Result<Self, ContractError> {
-        match s {
-            "sunflower" => Ok(PlantType::Sunflower),
-            "wheat" => Ok(PlantType::Wheat),
-            name => Err(ContractError::UnknownPlant {
-                name: name.to_string(),
-            }),
+        let name = s.trim().to_lowercase();
+        let is_valid = !name.is_empty()
+            && name.len() <= 32
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+        if !is_valid {
+            return Err(ContractError::UnknownPlant {
+                name: s.to_string(),
+            });
         }
+
+        Ok(PlantType(name))
     }
 }
 
-impl ToString for PlantType {
-    fn to_string(&self) -> String {
-        match self {
-            PlantType::Sunflower => String::from("sunflower"),
-            PlantType::Wheat => String::from("wheat"),
+impl fmt::Display for PlantType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cw_serde]
+pub struct PlantConfig {
+    pub stages: u64,
+    pub growth_period: u64,
+    /// Number of stages a plant survives without being watered.
+    pub watering_tolerance: u64,
+    pub min_seeds: u32,
+    pub max_seeds: u32,
+    /// Points added to the leaderboard for every harvest.
+    pub points: u64,
+    /// Crop tokens minted for every harvest, if the plant type has a crop token.
+    #[serde(default)]
+    pub produce: Uint128,
+}
+
+impl PlantConfig {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.stages == 0 || self.stages > params::MAX_PLANT_STAGES {
+            return Err(ContractError::InvalidPlantConfig {
+                reason: format!("stages must be between 1 and {}", params::MAX_PLANT_STAGES),
+            });
+        }
+
+        if self.growth_period == 0 {
+            return Err(ContractError::InvalidPlantConfig {
+                reason: "growth_period must be greater than zero".to_string(),
+            });
+        }
+
+        if self.min_seeds > self.max_seeds {
+            return Err(ContractError::InvalidPlantConfig {
+                reason: "min_seeds must not be greater than max_seeds".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cw_serde]
+pub enum CropEvent {
+    /// Dries out the watering of the current stage.
+    Drought,
+    /// Delays the growth by one growth period.
+    Pests,
+    /// Adds one seed to the harvest.
+    BumperCrop,
+    /// Doubles seeds and points of the harvest.
+    GoldenMutation,
+}
+
+impl fmt::Display for CropEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            CropEvent::Drought => "drought",
+            CropEvent::Pests => "pests",
+            CropEvent::BumperCrop => "bumper_crop",
+            CropEvent::GoldenMutation => "golden_mutation",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Consumable boost applied to a growing plant.
+#[cw_serde]
+pub enum Fertilizer {
+    /// Skips the rest of the current stage.
+    Growth,
+    /// Adds one seed to the harvest.
+    Yield,
+}
+
+impl FromStr for Fertilizer {
+    type Err = ContractError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "growth" => Ok(Fertilizer::Growth),
+            "yield" => Ok(Fertilizer::Yield),
+            _ => Err(ContractError::UnknownFertilizer {
+                name: s.to_string(),
+            }),
         }
     }
 }
@@ -46,43 +171,193 @@ pub struct KomplePlant {
     pub collection_id: u32,
 }
 
+/// Seed NFT held by the farm contract while its plant grows.
+#[cw_serde]
+pub struct SeedNft {
+    pub collection: String,
+    pub token_id: String,
+}
+
 #[cw_serde]
 pub struct Plant {
     pub r#type: PlantType,
     pub stages: u64,
     pub growth_period: u64,
     pub created_at: u64,
+    /// Stages watered by hand.
+    #[serde(default)]
+    pub watered: u64,
+    #[serde(default)]
+    pub last_watered_at: u64,
+    /// Height of every watering, only kept by plants stored before `watered` existed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub watered_at: Vec<u64>,
     pub komple: Option<KomplePlant>,
+    #[serde(default)]
+    pub watering_tolerance: u64,
+    #[serde(default)]
+    pub seed_nft: Option<SeedNft>,
+    #[serde(default)]
+    pub effects: Vec<CropEvent>,
+    #[serde(default)]
+    pub fertilizers: Vec<Fertilizer>,
 }
 
 impl Plant {
+    /// Folds the legacy `watered_at` heights into `watered`, returns whether the plant changed.
+    pub fn compact_watering(&mut self) -> bool {
+        let last_watered_at = match self.watered_at.last() {
+            None => return false,
+            Some(block) => *block,
+        };
+
+        self.watered = self.watered.max(self.watered_at.len() as u64);
+        self.last_watered_at = last_watered_at;
+        self.watered_at.clear();
+
+        true
+    }
+
+    pub fn water(&mut self, block: u64) {
+        self.watered += 1;
+        self.last_watered_at = block;
+    }
+
+    pub fn has_effect(&self, event: &CropEvent) -> bool {
+        self.effects.contains(event)
+    }
+
+    fn growth_delay(&self) -> u64 {
+        if self.has_effect(&CropEvent::Pests) {
+            self.growth_period
+        } else {
+            0
+        }
+    }
+
+    pub fn has_fertilizer(&self, fertilizer: &Fertilizer) -> bool {
+        self.fertilizers.contains(fertilizer)
+    }
+
+    fn growth_boost(&self) -> u64 {
+        if self.has_fertilizer(&Fertilizer::Growth) {
+            self.growth_period
+        } else {
+            0
+        }
+    }
+
+    /// Stage reached at `block`, a plant created in the future is in its first stage.
     pub fn get_current_stage(&self, block: u64) -> u64 {
-        let passed_time = block - self.created_at;
+        let passed_time = block
+            .saturating_sub(self.created_at)
+            .saturating_add(self.growth_boost())
+            .saturating_sub(self.growth_delay());
+
+        (passed_time / self.growth_period.max(1)).saturating_add(1)
+    }
+
+    /// Watered stages, every stage reached since `irrigated_since` counts as watered.
+    /// Irrigation starting after the plant died does not bring it back.
+    pub fn watered_stages(&self, block: u64, irrigated_since: Option<u64>) -> u64 {
+        let watered = self.watered;
+        let since = match irrigated_since {
+            None => return watered,
+            Some(since) => since.max(self.created_at),
+        };
 
-        passed_time / self.growth_period + 1
+        let first_irrigated = self.get_current_stage(since);
+        let current_stage = self.get_current_stage(block).min(self.stages);
+        let dies_at = watered
+            .saturating_add(2)
+            .saturating_add(self.watering_tolerance);
+        if current_stage < first_irrigated || first_irrigated >= dies_at {
+            return watered;
+        }
+
+        let irrigated = current_stage - first_irrigated + 1;
+        (watered.min(first_irrigated - 1).saturating_add(irrigated))
+            .max(watered)
+            .min(self.stages)
     }
 
-    pub fn is_dead(&self, block: u64) -> bool {
-        let watered_stages: u64 = self.watered_at.len().try_into().unwrap();
+    pub fn is_dead(&self, block: u64, irrigated_since: Option<u64>) -> bool {
+        let watered_stages = self.watered_stages(block, irrigated_since);
         let current_stage = self.get_current_stage(block);
 
-        watered_stages + 1 < current_stage
+        watered_stages
+            .saturating_add(1)
+            .saturating_add(self.watering_tolerance)
+            < current_stage
     }
 
-    pub fn can_water(&self, block: u64) -> bool {
-        let watered_stages: u64 = self.watered_at.len().try_into().unwrap();
+    pub fn can_water(&self, block: u64, irrigated_since: Option<u64>) -> bool {
+        let watered_stages = self.watered_stages(block, irrigated_since);
         let current_stage = self.get_current_stage(block);
 
-        watered_stages < self.stages && watered_stages == current_stage - 1
+        watered_stages < self.stages
+            && watered_stages < current_stage
+            && !self.is_dead(block, irrigated_since)
     }
 
-    pub fn can_harvest(&self, block: u64) -> bool {
-        let watered_stages: u64 = self.watered_at.len().try_into().unwrap();
+    pub fn can_harvest(&self, block: u64, irrigated_since: Option<u64>) -> bool {
+        let watered_stages = self.watered_stages(block, irrigated_since);
         let current_stage = self.get_current_stage(block);
 
         self.stages == current_stage && watered_stages == self.stages
     }
+
+    pub fn is_growing(&self, block: u64, irrigated_since: Option<u64>) -> bool {
+        !self.is_dead(block, irrigated_since)
+            && !self.can_harvest(block, irrigated_since)
+            && self.get_current_stage(block) <= self.stages
+    }
+
+    /// Applies the event unless the plant is already affected by it.
+    pub fn apply_event(&mut self, event: CropEvent, block: u64) -> bool {
+        if self.has_effect(&event) {
+            return false;
+        }
+
+        if event == CropEvent::Drought && self.watered == self.get_current_stage(block) {
+            self.watered -= 1;
+        }
+
+        self.effects.push(event);
+
+        true
+    }
+
+    /// Seed yield range of the harvest including crop events and fertilizers.
+    pub fn seed_yield(&self, config: &PlantConfig) -> (u32, u32) {
+        let mut min_seeds = config.min_seeds;
+        let mut max_seeds = config.max_seeds;
+
+        if self.has_effect(&CropEvent::BumperCrop) {
+            min_seeds = min_seeds.saturating_add(1);
+            max_seeds = max_seeds.saturating_add(1);
+        }
+
+        if self.has_fertilizer(&Fertilizer::Yield) {
+            min_seeds = min_seeds.saturating_add(1);
+            max_seeds = max_seeds.saturating_add(1);
+        }
+
+        if self.has_effect(&CropEvent::GoldenMutation) {
+            min_seeds = min_seeds.saturating_mul(2);
+            max_seeds = max_seeds.saturating_mul(2);
+        }
+
+        (min_seeds, max_seeds)
+    }
+
+    pub fn harvest_points(&self, config: &PlantConfig) -> u64 {
+        if self.has_effect(&CropEvent::GoldenMutation) {
+            config.points.saturating_mul(2)
+        } else {
+            config.points
+        }
+    }
 }
 
 #[cw_serde]
@@ -93,29 +368,33 @@ pub struct Slot {
 }
 
 impl Slot {
-    pub fn is_field_turned_meadow(&self, block: u64) -> bool {
+    pub fn is_field_turned_meadow(&self, block: u64, clock: &ClockMode) -> bool {
         self.r#type == SlotType::Field
             && match &self.plant {
-                None => block - self.created_at > params::FIELD_TURNS_MEADOW,
+                None => {
+                    block.saturating_sub(self.created_at)
+                        > clock.duration(params::FIELD_TURNS_MEADOW)
+                }
                 Some(_) => false,
             }
     }
 
-    pub fn get_real_type(&self, block: u64) -> SlotType {
-        if self.is_field_turned_meadow(block) {
+    pub fn get_real_type(&self, block: u64, clock: &ClockMode) -> SlotType {
+        if self.is_field_turned_meadow(block, clock) {
             return SlotType::Meadow;
         }
 
         self.r#type.clone()
     }
 
-    pub fn can_till(&self, block: u64) -> bool {
-        match self.get_real_type(block) {
+    pub fn can_till(&self, block: u64, clock: &ClockMode, irrigated_since: Option<u64>) -> bool {
+        match self.get_real_type(block, clock) {
             SlotType::Meadow => true,
             SlotType::Field => match &self.plant {
                 None => false,
-                Some(plant) => plant.is_dead(block),
+                Some(plant) => plant.is_dead(block, irrigated_since),
             },
+            SlotType::Sprinkler => false,
         }
     }
 }
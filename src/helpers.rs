@@ -1,6 +1,16 @@
-use cosmwasm_std::{Deps, MessageInfo};
+use cosmwasm_std::{
+    to_binary, Addr, CosmosMsg, Deps, MessageInfo, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use cw721::Cw721ExecuteMsg;
+use komple_framework_mint_module::msg::ExecuteMsg as KompleMintExecuteMsg;
 
-use crate::{state::INFORMATION, ContractError};
+use crate::{
+    farm::{KomplePlant, SeedNft},
+    msg::Role,
+    state::{INFORMATION, ROLES},
+    ContractError,
+};
 
 // HELPERS
 pub fn admin_error_check(deps: Deps, info: MessageInfo) -> Result<(), ContractError> {
@@ -12,6 +22,90 @@ pub fn admin_error_check(deps: Deps, info: MessageInfo) -> Result<(), ContractEr
     Ok(())
 }
 
-pub fn throw_err(msg: &str) -> ContractError {
-    ContractError::Std(cosmwasm_std::StdError::generic_err(msg))
+/// Lets the admin and the holders of `role` through.
+pub fn role_error_check(deps: Deps, info: MessageInfo, role: Role) -> Result<(), ContractError> {
+    let contract_info = INFORMATION.load(deps.storage)?;
+    if contract_info.admin == info.sender {
+        return Ok(());
+    }
+
+    let roles = ROLES
+        .may_load(deps.storage, info.sender.as_str())?
+        .unwrap_or_default();
+    if !roles.contains(&role) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(())
+}
+
+/// Address receiving the fees and sales of the contract.
+pub fn treasury(storage: &dyn Storage) -> StdResult<String> {
+    let information = INFORMATION.load(storage)?;
+
+    Ok(information.treasury.unwrap_or(information.admin))
+}
+
+pub fn transfer_nft_msg(nft: &SeedNft, recipient: String) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft.collection.clone(),
+        msg: to_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient,
+            token_id: nft.token_id.clone(),
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
+pub fn burn_nft_msg(nft: &SeedNft) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: nft.collection.clone(),
+        msg: to_binary(&Cw721ExecuteMsg::Burn {
+            token_id: nft.token_id.clone(),
+        })?,
+        funds: vec![],
+    }
+    .into())
+}
+
+pub fn mint_crop_tokens_msg(
+    token: &Addr,
+    recipient: String,
+    amount: Uint128,
+) -> StdResult<CosmosMsg> {
+    Ok(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Mint { recipient, amount })?,
+        funds: vec![],
+    }
+    .into())
+}
+
+pub fn mint_seeds(
+    plant: KomplePlant,
+    recipient: String,
+    seeds: u32,
+    storage: &dyn Storage,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let information = INFORMATION.load(storage)?;
+    let admin_mint_nft = match information.komple_mint_addr {
+        None => Err(ContractError::KompleMintMissing {}),
+        Some(komple_mint_addr) => Ok(WasmMsg::Execute {
+            contract_addr: komple_mint_addr,
+            msg: to_binary::<KompleMintExecuteMsg>(&KompleMintExecuteMsg::AdminMint {
+                collection_id: plant.collection_id,
+                recipient,
+                metadata_id: Some(plant.metadata_id),
+            })?,
+            funds: vec![],
+        }),
+    }?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for _i in 0..seeds {
+        messages.push(admin_mint_nft.clone().into());
+    }
+
+    Ok(messages)
 }
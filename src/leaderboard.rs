@@ -0,0 +1,236 @@
+use cosmwasm_std::{Order, StdError, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::{
+    farm::PlantType,
+    msg::LeaderboardEntry,
+    params,
+    state::{crop_points, points, season_points, CropPoints, Points, SeasonPoints},
+    ContractError,
+};
+
+fn parse_plant_type(name: &str) -> StdResult<PlantType> {
+    name.parse()
+        .map_err(|err: ContractError| StdError::generic_err(err.to_string()))
+}
+
+/// Pages start after an address on the leaderboard, restarting at the top would repeat entries.
+fn unknown_cursor(addr: &str) -> StdError {
+    StdError::generic_err(format!("{} is not on the leaderboard", addr))
+}
+
+/// Position of the points in the leaderboard, ties are ordered by address.
+fn rank(storage: &dyn Storage, pts: &Points) -> usize {
+    let start = Bound::exclusive((pts.total(), pts.addr.as_str()));
+
+    points()
+        .idx
+        .total
+        .keys_raw(storage, Some(start), None, Order::Ascending)
+        .count()
+        + 1
+}
+
+fn crop_rank(storage: &dyn Storage, pts: &CropPoints) -> usize {
+    let start = Bound::exclusive((pts.amount, (pts.plant_type.as_str(), pts.addr.as_str())));
+
+    crop_points()
+        .idx
+        .amount
+        .sub_prefix(pts.plant_type.clone())
+        .keys_raw(storage, Some(start), None, Order::Ascending)
+        .count()
+        + 1
+}
+
+fn season_rank(storage: &dyn Storage, pts: &SeasonPoints) -> usize {
+    let start = Bound::exclusive((pts.total, (pts.season_id, pts.addr.as_str())));
+
+    season_points()
+        .idx
+        .total
+        .sub_prefix(pts.season_id)
+        .keys_raw(storage, Some(start), None, Order::Ascending)
+        .count()
+        + 1
+}
+
+/// Returns the `(address, total)` entries after `start_after` with the rank of the first one.
+pub fn total_page(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: usize,
+) -> StdResult<(usize, Vec<(String, u64)>)> {
+    let start = match start_after {
+        None => None,
+        Some(addr) => Some(
+            points()
+                .may_load(storage, addr.as_str())?
+                .ok_or_else(|| unknown_cursor(&addr))?,
+        ),
+    };
+    let (max, first_rank) = match &start {
+        None => (None, 1),
+        Some(pts) => (
+            Some(Bound::exclusive((pts.total(), pts.addr.as_str()))),
+            rank(storage, pts) + 1,
+        ),
+    };
+
+    let page = points()
+        .idx
+        .total
+        .range(storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|res| {
+            let (_, pts) = res?;
+            let total = pts.total();
+
+            Ok((pts.addr, total))
+        })
+        .collect::<StdResult<_>>()?;
+
+    Ok((first_rank, page))
+}
+
+fn crop_page(
+    storage: &dyn Storage,
+    plant_type: &PlantType,
+    start_after: Option<String>,
+    limit: usize,
+) -> StdResult<(usize, Vec<(String, u64)>)> {
+    let start = match start_after {
+        None => None,
+        Some(addr) => Some(
+            crop_points()
+                .may_load(storage, (plant_type.as_str(), addr.as_str()))?
+                .ok_or_else(|| unknown_cursor(&addr))?,
+        ),
+    };
+    let (max, first_rank) = match &start {
+        None => (None, 1),
+        Some(pts) => (
+            Some(Bound::exclusive((
+                pts.amount,
+                (pts.plant_type.as_str(), pts.addr.as_str()),
+            ))),
+            crop_rank(storage, pts) + 1,
+        ),
+    };
+
+    let page = crop_points()
+        .idx
+        .amount
+        .sub_prefix(plant_type.to_string())
+        .range(storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|res| res.map(|(_, pts)| (pts.addr, pts.amount)))
+        .collect::<StdResult<_>>()?;
+
+    Ok((first_rank, page))
+}
+
+/// Standings of a season, the first entry is ranked first.
+pub fn season_page(
+    storage: &dyn Storage,
+    season_id: u64,
+    start_after: Option<String>,
+    limit: usize,
+) -> StdResult<(usize, Vec<(String, u64)>)> {
+    let start = match start_after {
+        None => None,
+        Some(addr) => Some(
+            season_points()
+                .may_load(storage, (season_id, addr.as_str()))?
+                .ok_or_else(|| unknown_cursor(&addr))?,
+        ),
+    };
+    let (max, first_rank) = match &start {
+        None => (None, 1),
+        Some(pts) => (
+            Some(Bound::exclusive((
+                pts.total,
+                (pts.season_id, pts.addr.as_str()),
+            ))),
+            season_rank(storage, pts) + 1,
+        ),
+    };
+
+    let page = season_points()
+        .idx
+        .total
+        .sub_prefix(season_id)
+        .range(storage, None, max, Order::Descending)
+        .take(limit)
+        .map(|res| res.map(|(_, pts)| (pts.addr, pts.total)))
+        .collect::<StdResult<_>>()?;
+
+    Ok((first_rank, page))
+}
+
+pub fn leaderboard_entries(first_rank: usize, page: Vec<(String, u64)>) -> Vec<LeaderboardEntry> {
+    page.into_iter()
+        .enumerate()
+        .map(|(i, (address, total))| LeaderboardEntry {
+            rank: (first_rank + i) as u64,
+            address,
+            total,
+        })
+        .collect()
+}
+
+pub fn query_leaderboard(
+    storage: &dyn Storage,
+    plant_type: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LeaderboardEntry>> {
+    let limit = limit
+        .unwrap_or(params::LEADERBOARD_DEFAULT_LIMIT)
+        .min(params::LEADERBOARD_MAX_LIMIT) as usize;
+
+    let (first_rank, page) = match plant_type {
+        None => total_page(storage, start_after, limit)?,
+        Some(name) => crop_page(storage, &parse_plant_type(&name)?, start_after, limit)?,
+    };
+
+    Ok(leaderboard_entries(first_rank, page))
+}
+
+pub fn query_season_leaderboard(
+    storage: &dyn Storage,
+    season_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LeaderboardEntry>> {
+    let limit = limit
+        .unwrap_or(params::LEADERBOARD_DEFAULT_LIMIT)
+        .min(params::LEADERBOARD_MAX_LIMIT) as usize;
+    let (first_rank, page) = season_page(storage, season_id, start_after, limit)?;
+
+    Ok(leaderboard_entries(first_rank, page))
+}
+
+pub fn query_rank(
+    storage: &dyn Storage,
+    address: String,
+    plant_type: Option<String>,
+) -> StdResult<Option<LeaderboardEntry>> {
+    let entry = match plant_type {
+        None => points()
+            .may_load(storage, address.as_str())?
+            .map(|pts| (rank(storage, &pts), pts.total())),
+        Some(name) => {
+            let plant_type = parse_plant_type(&name)?;
+            crop_points()
+                .may_load(storage, (plant_type.as_str(), address.as_str()))?
+                .map(|pts| (crop_rank(storage, &pts), pts.amount))
+        }
+    };
+
+    Ok(entry.map(|(rank, total)| LeaderboardEntry {
+        rank: rank as u64,
+        address,
+        total,
+    }))
+}
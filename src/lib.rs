@@ -1,10 +1,19 @@
+pub mod actions;
+pub mod admin;
 pub mod contract;
 mod error;
 pub mod farm;
 pub mod helpers;
+pub mod leaderboard;
+pub mod market;
 pub mod msg;
 pub mod params;
+pub mod pause;
+pub mod prizes;
+pub mod randomness;
 pub mod receive;
+pub mod season;
+pub mod shop;
 pub mod state;
 #[cfg(test)]
 pub mod tests;
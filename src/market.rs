@@ -0,0 +1,244 @@
+use cosmwasm_std::{
+    coin, from_binary, to_binary, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order,
+    Response, StdResult, Storage, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::Bound;
+
+use crate::{
+    farm::PlantType,
+    helpers::{role_error_check, transfer_nft_msg, treasury},
+    msg::{Cw20HookMsg, MarketConfig, PausableAction, Role},
+    params,
+    pause::ensure_action_enabled,
+    state::{listings, ListedItem, Listing, CROP_TOKENS, LISTING_LAST_ID, MARKET_CONFIG},
+    ContractError,
+};
+
+pub fn validate_market_config(config: &MarketConfig) -> Result<(), ContractError> {
+    if config.fee_bps > params::MAX_MARKET_FEE_BPS {
+        return Err(ContractError::InvalidMarketConfig {
+            reason: format!("fee_bps must not exceed {}", params::MAX_MARKET_FEE_BPS),
+        });
+    }
+
+    Ok(())
+}
+
+fn transfer_item_msg(item: &ListedItem, recipient: String) -> StdResult<CosmosMsg> {
+    match item {
+        ListedItem::SeedNft(nft) => transfer_nft_msg(nft, recipient),
+        ListedItem::CropTokens { token, amount } => Ok(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient,
+                amount: *amount,
+            })?,
+            funds: vec![],
+        }
+        .into()),
+    }
+}
+
+fn load_listing(storage: &dyn Storage, listing_id: u64) -> Result<Listing, ContractError> {
+    listings()
+        .may_load(storage, listing_id)?
+        .ok_or(ContractError::UnknownListing { listing_id })
+}
+
+/// Stores a listing of an item the contract already holds.
+pub fn create_listing(
+    storage: &mut dyn Storage,
+    seller: String,
+    plant_type: PlantType,
+    item: ListedItem,
+    price: Coin,
+    block: u64,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(storage, PausableAction::Market)?;
+
+    if price.amount.is_zero() {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    let id = LISTING_LAST_ID.may_load(storage)?.unwrap_or(0) + 1;
+    LISTING_LAST_ID.save(storage, &id)?;
+
+    let listing = Listing {
+        id,
+        seller,
+        plant_type,
+        item,
+        price,
+        created_at: block,
+    };
+    listings().save(storage, id, &listing)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "list")
+        .add_attribute("listing_id", id.to_string())
+        .add_attribute("plant_type", listing.plant_type.to_string()))
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let plant_type = CROP_TOKENS
+        .range(deps.storage, None, None, Order::Ascending)
+        .find_map(|res| match res {
+            Ok((plant_type, token)) if token == info.sender => Some(Ok(plant_type)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .transpose()?
+        .ok_or_else(|| ContractError::UnknownCropToken {
+            address: info.sender.to_string(),
+        })?;
+
+    match from_binary(&msg.msg)? {
+        Cw20HookMsg::List { price } => create_listing(
+            deps.storage,
+            msg.sender,
+            plant_type.parse()?,
+            ListedItem::CropTokens {
+                token: info.sender,
+                amount: msg.amount,
+            },
+            price,
+            env.block.height,
+        ),
+    }
+}
+
+pub fn buy_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    listing_id: u64,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Market)?;
+
+    let listing = load_listing(deps.storage, listing_id)?;
+
+    if info.funds != [listing.price.clone()] {
+        return Err(ContractError::InvalidFunds {
+            required: Some(listing.price),
+        });
+    }
+
+    let config = MARKET_CONFIG.load(deps.storage)?;
+    let fee = listing
+        .price
+        .amount
+        .multiply_ratio(config.fee_bps, params::BASIS_POINTS);
+    let proceeds = listing.price.amount - fee;
+    let denom = listing.price.denom.clone();
+
+    listings().remove(deps.storage, listing_id)?;
+
+    let mut messages = vec![transfer_item_msg(&listing.item, info.sender.to_string())?];
+    if !proceeds.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: listing.seller,
+                amount: vec![coin(proceeds.u128(), denom.clone())],
+            }
+            .into(),
+        );
+    }
+    if !fee.is_zero() {
+        messages.push(
+            BankMsg::Send {
+                to_address: treasury(deps.storage)?,
+                amount: vec![coin(fee.u128(), denom)],
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_listing")
+        .add_attribute("listing_id", listing_id.to_string())
+        .add_attribute("fee", fee)
+        .add_messages(messages))
+}
+
+pub fn cancel_listing(
+    deps: DepsMut,
+    info: MessageInfo,
+    listing_id: u64,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Market)?;
+
+    let listing = load_listing(deps.storage, listing_id)?;
+
+    if info.sender != listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    listings().remove(deps.storage, listing_id)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_listing")
+        .add_attribute("listing_id", listing_id.to_string())
+        .add_message(transfer_item_msg(&listing.item, listing.seller)?))
+}
+
+pub fn update_market_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: MarketConfig,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+    validate_market_config(&config)?;
+
+    MARKET_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_market_config"))
+}
+
+pub fn query_listings(
+    deps: Deps,
+    seller: Option<String>,
+    plant_type: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Listing>> {
+    let limit = limit
+        .unwrap_or(params::DEFAULT_LIMIT)
+        .min(params::MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    // Plant types are stored normalized, unknown names simply match nothing.
+    let plant_type = plant_type.map(|name| {
+        name.parse::<PlantType>()
+            .map(|plant_type| plant_type.to_string())
+            .unwrap_or(name)
+    });
+
+    let range = match (&seller, &plant_type) {
+        (Some(seller), _) => listings().idx.seller.prefix(seller.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, Some(plant_type)) => listings().idx.plant_type.prefix(plant_type.clone()).range(
+            deps.storage,
+            start,
+            None,
+            Order::Ascending,
+        ),
+        (None, None) => listings().range(deps.storage, start, None, Order::Ascending),
+    };
+
+    range
+        .map(|res| res.map(|(_, listing)| listing))
+        .filter(|res| match (res, &plant_type) {
+            (Ok(listing), Some(plant_type)) => listing.plant_type.as_str() == plant_type,
+            _ => true,
+        })
+        .take(limit)
+        .collect()
+}
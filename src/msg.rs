@@ -1,15 +1,23 @@
+use std::fmt;
+
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw20::Cw20ReceiveMsg;
 use cw721::Cw721ReceiveMsg;
 use nois::NoisCallback;
 
-use crate::state::{FarmProfile, FarmProfileDto};
+use crate::{
+    farm::{ClockMode, KomplePlant, PlantConfig, SeedNft, Slot},
+    state::{FarmLayout, FarmProfileDto, Listing, PendingNoisJob},
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub admin: Option<String>,
     pub komple_mint_addr: Option<String>,
     pub nois_proxy: Option<String>,
+    /// Unit of the farm timers, block height by default. It can not be changed later.
+    pub clock_mode: Option<ClockMode>,
 }
 
 #[cw_serde]
@@ -19,14 +27,29 @@ pub struct MigrateMsg {}
 pub enum ExecuteMsg {
     Start {},
     SetupFarm {
-        farm: FarmProfile,
+        farm: FarmLayout,
+        addr: Addr,
+    },
+    /// Replaces single plots of an existing farm, the rest of the farm is kept.
+    PatchPlots {
         addr: Addr,
+        patches: Vec<(u8, u8, Slot)>,
     },
     Stop {},
     TillGround {
         x: u8,
         y: u8,
     },
+    /// Builds a sprinkler on a meadow, paid with points. It waters the neighbouring plots.
+    BuildSprinkler {
+        x: u8,
+        y: u8,
+    },
+    /// Turns the sprinkler back into a meadow.
+    RemoveSprinkler {
+        x: u8,
+        y: u8,
+    },
     WaterPlant {
         x: u8,
         y: u8,
@@ -35,18 +58,162 @@ pub enum ExecuteMsg {
         x: u8,
         y: u8,
     },
+    /// Applies the actions to the senders farm in order and saves it once.
+    /// With `skip_failures` a failing action is reported instead of reverting the whole batch.
+    /// Sent funds are split evenly between the harvests to pay for their randomness.
+    Batch {
+        actions: Vec<FarmAction>,
+        skip_failures: bool,
+    },
+    ExpandFarm {
+        payment: PaymentMethod,
+    },
+    /// Updates the contract information, the admin can only change through `ProposeAdmin`.
     UpdateContractInformation {
         contract_information: ContractInformation,
     },
+    /// Proposes a new admin which has to accept, `None` withdraws the proposal.
+    ProposeAdmin {
+        admin: Option<String>,
+    },
+    /// Makes the proposed admin the admin.
+    AcceptAdmin {},
+    GrantRole {
+        address: String,
+        role: Role,
+    },
+    RevokeRole {
+        address: String,
+        role: Role,
+    },
+    UpdateExpansionConfig {
+        config: ExpansionConfig,
+    },
+    SetPlantType {
+        name: String,
+        config: PlantConfig,
+    },
+    /// Sets the price of the seeds of a plant type in the shop, `None` removes them from the shop.
+    SetSeedPrice {
+        plant_type: String,
+        price: Option<SeedPrice>,
+    },
+    BuySeeds {
+        plant_type: String,
+        amount: u32,
+    },
+    /// Sets the CW20 contract minting the produce of a plant type, `None` stops the minting.
+    SetCropToken {
+        plant_type: String,
+        token: Option<String>,
+    },
+    UpdateNoisConfig {
+        config: NoisConfig,
+    },
+    UpdateCropEventsConfig {
+        config: CropEventsConfig,
+    },
+    /// Requests randomness to roll crop events for the plants on the senders farm.
+    RollCropEvents {},
+    /// Schedules a season collecting points from `start` until `end` block height.
+    CreateSeason {
+        start: u64,
+        end: u64,
+    },
+    /// Stores the final standings of an ended season.
+    CloseSeason {
+        season_id: u64,
+    },
+    UpdatePrizeConfig {
+        config: PrizeConfig,
+    },
+    /// Adds the sent funds to the prize pool.
+    FundPrizePool {},
+    /// Pays the top ranks of a closed season from the prize pool,
+    /// or of the all time leaderboard when `season_id` is not set.
+    DistributePrizes {
+        season_id: Option<u64>,
+    },
+    /// Completes a timed out Nois job with its deterministic fallback outcome.
+    SettleNoisJob {
+        job_id: String,
+    },
+    /// Requests fresh randomness for a timed out Nois job, the sent funds pay the proxy.
+    RetryNoisJob {
+        job_id: String,
+    },
     ReceiveNft(Cw721ReceiveMsg),
+    Receive(Cw20ReceiveMsg),
+    BuyListing {
+        listing_id: u64,
+    },
+    /// Returns the listed item to the seller.
+    CancelListing {
+        listing_id: u64,
+    },
+    UpdateMarketConfig {
+        config: MarketConfig,
+    },
     NoisReceive {
         callback: NoisCallback,
     },
+    /// Pauses or resumes every player action, admin messages keep working.
+    SetPaused {
+        paused: bool,
+    },
+    /// Pauses or resumes a single kind of action.
+    SetActionPaused {
+        action: PausableAction,
+        paused: bool,
+    },
+}
+
+#[cw_serde]
+pub enum PaymentMethod {
+    Funds,
+    Points,
+}
+
+#[cw_serde]
+pub enum FarmAction {
+    Till { x: u8, y: u8 },
+    Water { x: u8, y: u8 },
+    Harvest { x: u8, y: u8 },
+}
+
+/// Outcome of a batched action, set as the response data of `Batch`.
+#[cw_serde]
+pub struct FarmActionResult {
+    pub action: FarmAction,
+    pub error: Option<String>,
 }
 
 #[cw_serde]
 pub enum Cw721HookMsg {
-    Seed { x: u8, y: u8 },
+    Seed {
+        x: u8,
+        y: u8,
+    },
+    /// Same as `Seed`, but the NFT is sent back instead of failing the transfer.
+    TrySeed {
+        x: u8,
+        y: u8,
+    },
+    /// Applies the fertilizer NFT to the plant, the NFT is burned.
+    Fertilize {
+        x: u8,
+        y: u8,
+    },
+    /// Lists the NFT on the marketplace.
+    List {
+        price: Coin,
+    },
+}
+
+#[cw_serde]
+pub enum Cw20HookMsg {
+    /// Lists all the sent crop tokens on the marketplace for `price`.
+    List { price: Coin },
 }
 
 #[cw_serde]
@@ -59,8 +226,125 @@ pub enum QueryMsg {
     #[returns(FarmProfileDto)]
     GetFarmProfile { address: String },
 
-    #[returns(Vec<(u64, String)>)]
-    Leaderboard {},
+    // Seed NFTs held by the contract for plants on a users farm
+    #[returns(Vec<FarmNft>)]
+    FarmNfts { address: String },
+
+    /// All time leaderboard, or the leaderboard of a single crop when `plant_type` is set.
+    #[returns(Vec<LeaderboardEntry>)]
+    Leaderboard {
+        plant_type: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Option<LeaderboardEntry>)]
+    Rank {
+        address: String,
+        plant_type: Option<String>,
+    },
+
+    #[returns(Vec<Season>)]
+    Seasons {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Season)]
+    Season { season_id: u64 },
+
+    #[returns(Option<Season>)]
+    CurrentSeason {},
+
+    /// Live standings of a season.
+    #[returns(Vec<LeaderboardEntry>)]
+    SeasonLeaderboard {
+        season_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Final standings of a closed season, paginated by rank.
+    #[returns(Vec<LeaderboardEntry>)]
+    SeasonResults {
+        season_id: u64,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Option<PrizeConfig>)]
+    PrizeConfig {},
+
+    #[returns(Uint128)]
+    PrizePool {},
+
+    /// Prizes paid for a season, or for the all time leaderboard when `season_id` is not set.
+    #[returns(Vec<PrizePayout>)]
+    PrizePayouts { season_id: Option<u64> },
+
+    #[returns(ExpansionConfig)]
+    ExpansionConfig {},
+
+    #[returns(ClockMode)]
+    ClockMode {},
+
+    #[returns(NoisConfig)]
+    NoisConfig {},
+
+    #[returns(CropEventsConfig)]
+    CropEventsConfig {},
+
+    #[returns(Vec<PendingNoisJobResponse>)]
+    PendingNoisJobs {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Vec<PlantTypeResponse>)]
+    PlantTypes {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(PlantConfig)]
+    PlantType { name: String },
+
+    #[returns(Vec<SeedPriceResponse>)]
+    SeedPrices {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Listings filtered by seller or plant type, ordered by id.
+    #[returns(Vec<Listing>)]
+    Listings {
+        seller: Option<String>,
+        plant_type: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    #[returns(Listing)]
+    Listing { listing_id: u64 },
+
+    #[returns(MarketConfig)]
+    MarketConfig {},
+
+    #[returns(Vec<CropTokenResponse>)]
+    CropTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    #[returns(PauseStatus)]
+    PauseStatus {},
+
+    #[returns(Option<String>)]
+    PendingAdmin {},
+
+    /// Roles granted to the address, the admin holds every role without being listed.
+    #[returns(Vec<Role>)]
+    Roles { address: String },
 }
 
 // === RESPONSES ===
@@ -70,8 +354,166 @@ pub struct KompleCollection {
     pub id: u32,
 }
 
+#[cw_serde]
+pub struct FarmNft {
+    pub x: u8,
+    pub y: u8,
+    pub nft: SeedNft,
+}
+
+#[cw_serde]
+pub struct PendingNoisJobResponse {
+    pub job_id: String,
+    pub job: PendingNoisJob,
+}
+
+#[cw_serde]
+pub struct LeaderboardEntry {
+    pub rank: u64,
+    pub address: String,
+    pub total: u64,
+}
+
+#[cw_serde]
+pub struct Season {
+    pub id: u64,
+    pub start: u64,
+    pub end: u64,
+    pub closed: bool,
+}
+
+#[cw_serde]
+pub struct PrizePayout {
+    pub rank: u64,
+    pub address: String,
+    pub amount: Coin,
+}
+
+#[cw_serde]
+pub struct PlantTypeResponse {
+    pub name: String,
+    pub config: PlantConfig,
+}
+
+#[cw_serde]
+pub struct SeedPriceResponse {
+    pub plant_type: String,
+    pub price: SeedPrice,
+}
+
+#[cw_serde]
+pub struct CropTokenResponse {
+    pub plant_type: String,
+    pub token: Addr,
+}
+
 #[cw_serde]
 pub struct ContractInformation {
     pub admin: String,
     pub komple_mint_addr: Option<String>,
+    /// Receives the proceeds of the seed shop, the admin does when not set.
+    #[serde(default)]
+    pub treasury: Option<String>,
+}
+
+#[cw_serde]
+pub struct SeedPrice {
+    /// Price of a single seed.
+    pub price: Coin,
+    /// Komple collection and metadata the bought seeds are minted from.
+    pub komple: KomplePlant,
+}
+
+#[cw_serde]
+pub struct ExpansionConfig {
+    /// How many rows and columns are added by a single expansion.
+    pub step: u8,
+    /// Farms can not grow beyond `max_size` x `max_size` plots.
+    pub max_size: u8,
+    /// Price of one expansion in native funds, `None` disables paying with funds.
+    pub price: Option<Coin>,
+    /// Price of one expansion in harvest points, `None` disables paying with points.
+    pub points_price: Option<u64>,
+}
+
+#[cw_serde]
+pub struct NoisConfig {
+    /// Blocks after which a pending job can be settled or retried.
+    pub job_timeout: u64,
+}
+
+#[cw_serde]
+pub struct CropEventsConfig {
+    /// Blocks a farm has to wait between two rolls.
+    pub cooldown: u64,
+    /// Chances of the events in percent, rolled separately for every growing plant.
+    pub drought: u8,
+    pub pests: u8,
+    pub bumper_crop: u8,
+    pub golden_mutation: u8,
+}
+
+#[cw_serde]
+pub struct PrizeConfig {
+    pub denom: String,
+    /// Share of the prize pool in basis points for each rank, starting with the first.
+    pub shares: Vec<u16>,
+}
+
+#[cw_serde]
+pub struct MarketConfig {
+    /// Part of every sale paid to the treasury, in basis points.
+    pub fee_bps: u16,
+}
+
+/// Actions which can be paused on their own.
+#[cw_serde]
+pub enum PausableAction {
+    Harvest,
+    /// Planting seed NFTs sent to the farm.
+    Seed,
+    /// Nois callbacks and settling or retrying timed out jobs.
+    Nois,
+    Shop,
+    Market,
+}
+
+impl fmt::Display for PausableAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PausableAction::Harvest => write!(f, "Harvesting"),
+            PausableAction::Seed => write!(f, "Seeding"),
+            PausableAction::Nois => write!(f, "Nois"),
+            PausableAction::Shop => write!(f, "The shop"),
+            PausableAction::Market => write!(f, "The market"),
+        }
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseStatus {
+    pub paused: bool,
+    pub paused_actions: Vec<PausableAction>,
+}
+
+/// Permissions the admin can grant to other addresses.
+#[cw_serde]
+pub enum Role {
+    /// Sets up farms and manages plant types and seasons.
+    GameMaster,
+    /// Manages the prices, fees, crop tokens and the prize pool.
+    Treasurer,
+    /// Pauses the game and single actions.
+    Operator,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Role::GameMaster => write!(f, "game_master"),
+            Role::Treasurer => write!(f, "treasurer"),
+            Role::Operator => write!(f, "operator"),
+        }
+    }
 }
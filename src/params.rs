@@ -1,3 +1,36 @@
 pub const GROWTH_PERIOD_SUNFLOWER: u64 = 10;
 pub const GROWTH_PERIOD_WHEET: u64 = 10;
+pub const STAGES_SUNFLOWER: u64 = 5;
+pub const STAGES_WHEAT: u64 = 4;
+pub const MAX_PLANT_STAGES: u64 = 100;
+pub const PRODUCE_SUNFLOWER: u128 = 1_000_000;
+pub const PRODUCE_WHEAT: u128 = 1_000_000;
+pub const MIN_SEEDS: u32 = 2;
+pub const MAX_SEEDS: u32 = 5;
+pub const MAX_SEEDS_PURCHASE: u32 = 10;
+pub const MAX_BATCH_ACTIONS: usize = 225;
+pub const DEFAULT_LIMIT: u32 = 10;
+pub const MAX_LIMIT: u32 = 30;
+/// The leaderboard used to return the top 100 at once, so its pages keep that size.
+pub const LEADERBOARD_DEFAULT_LIMIT: u32 = 100;
+pub const LEADERBOARD_MAX_LIMIT: u32 = 100;
 pub const FIELD_TURNS_MEADOW: u64 = 10;
+/// Block time used to convert the default durations for `ClockMode::Seconds`.
+pub const BLOCK_TIME_SECONDS: u64 = 6;
+pub const EXPANSION_STEP: u8 = 1;
+pub const EXPANSION_MAX_SIZE: u8 = 15;
+pub const SPRINKLER_POINTS_PRICE: u64 = 20;
+pub const EXPANSION_POINTS_PRICE: u64 = 10;
+pub const NOIS_JOB_TIMEOUT: u64 = 100;
+/// Upper bound of the Nois job timeout, about two months of blocks.
+pub const MAX_NOIS_JOB_TIMEOUT: u64 = 1_000_000;
+pub const CROP_EVENTS_COOLDOWN: u64 = 100;
+pub const MAX_CROP_EVENTS_COOLDOWN: u64 = 1_000_000;
+pub const DROUGHT_CHANCE: u8 = 10;
+pub const PESTS_CHANCE: u8 = 10;
+pub const BUMPER_CROP_CHANCE: u8 = 10;
+pub const GOLDEN_MUTATION_CHANCE: u8 = 2;
+pub const SEASON_RESULTS_SIZE: usize = 100;
+pub const BASIS_POINTS: u16 = 10_000;
+pub const MARKET_FEE_BPS: u16 = 250;
+pub const MAX_MARKET_FEE_BPS: u16 = 1_000;
@@ -0,0 +1,97 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response, StdResult, Storage};
+
+use crate::{
+    helpers::role_error_check,
+    msg::{ExecuteMsg, PausableAction, PauseStatus, Role},
+    state::PAUSE_STATUS,
+    ContractError,
+};
+
+pub fn load_pause_status(storage: &dyn Storage) -> StdResult<PauseStatus> {
+    Ok(PAUSE_STATUS.may_load(storage)?.unwrap_or_default())
+}
+
+/// Fails for player messages while the game is paused.
+/// Admin messages keep working and Nois callbacks are only stopped by their own breaker.
+pub fn ensure_not_paused(storage: &dyn Storage, msg: &ExecuteMsg) -> Result<(), ContractError> {
+    let exempt = matches!(
+        msg,
+        ExecuteMsg::SetupFarm { .. }
+            | ExecuteMsg::PatchPlots { .. }
+            | ExecuteMsg::UpdateContractInformation { .. }
+            | ExecuteMsg::ProposeAdmin { .. }
+            | ExecuteMsg::AcceptAdmin { .. }
+            | ExecuteMsg::GrantRole { .. }
+            | ExecuteMsg::RevokeRole { .. }
+            | ExecuteMsg::UpdateExpansionConfig { .. }
+            | ExecuteMsg::SetPlantType { .. }
+            | ExecuteMsg::SetSeedPrice { .. }
+            | ExecuteMsg::SetCropToken { .. }
+            | ExecuteMsg::UpdateNoisConfig { .. }
+            | ExecuteMsg::UpdateCropEventsConfig { .. }
+            | ExecuteMsg::CreateSeason { .. }
+            | ExecuteMsg::UpdatePrizeConfig { .. }
+            | ExecuteMsg::FundPrizePool { .. }
+            | ExecuteMsg::DistributePrizes { .. }
+            | ExecuteMsg::UpdateMarketConfig { .. }
+            | ExecuteMsg::NoisReceive { .. }
+            | ExecuteMsg::SetPaused { .. }
+            | ExecuteMsg::SetActionPaused { .. }
+    );
+
+    if !exempt && load_pause_status(storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    Ok(())
+}
+
+pub fn ensure_action_enabled(
+    storage: &dyn Storage,
+    action: PausableAction,
+) -> Result<(), ContractError> {
+    if load_pause_status(storage)?.paused_actions.contains(&action) {
+        return Err(ContractError::ActionPaused { action });
+    }
+
+    Ok(())
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Operator)?;
+
+    let mut status = load_pause_status(deps.storage)?;
+    status.paused = paused;
+    PAUSE_STATUS.save(deps.storage, &status)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_paused")
+        .add_attribute("paused", paused.to_string()))
+}
+
+pub fn set_action_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    action: PausableAction,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Operator)?;
+
+    let mut status = load_pause_status(deps.storage)?;
+    status
+        .paused_actions
+        .retain(|paused_action| *paused_action != action);
+    if paused {
+        status.paused_actions.push(action.clone());
+    }
+    PAUSE_STATUS.save(deps.storage, &status)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_action_paused")
+        .add_attribute("paused_action", action.to_string())
+        .add_attribute("paused", paused.to_string()))
+}
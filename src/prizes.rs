@@ -0,0 +1,180 @@
+use cosmwasm_std::{
+    coin, BankMsg, DepsMut, MessageInfo, Order, Response, StdResult, Storage, Uint128,
+};
+
+use crate::{
+    helpers::role_error_check,
+    leaderboard::{leaderboard_entries, total_page},
+    msg::{LeaderboardEntry, PrizeConfig, PrizePayout, Role},
+    params,
+    season::load_season,
+    state::{ALL_TIME_PRIZE_PAYOUTS, PRIZE_CONFIG, PRIZE_PAYOUTS, PRIZE_POOL, SEASON_RESULTS},
+    ContractError,
+};
+
+fn validate_prize_config(storage: &dyn Storage, config: &PrizeConfig) -> Result<(), ContractError> {
+    if config.shares.is_empty() {
+        return Err(ContractError::InvalidPrizeConfig {
+            reason: "at least one rank must be paid".to_string(),
+        });
+    }
+
+    let total: u32 = config.shares.iter().map(|share| u32::from(*share)).sum();
+    if total > params::BASIS_POINTS.into() {
+        return Err(ContractError::InvalidPrizeConfig {
+            reason: format!("shares exceed {}", params::BASIS_POINTS),
+        });
+    }
+
+    // The pool is tracked as a single amount, so its denom can't change while funded.
+    if let Some(current) = PRIZE_CONFIG.may_load(storage)? {
+        let pool = PRIZE_POOL.may_load(storage)?.unwrap_or_default();
+        if current.denom != config.denom && !pool.is_zero() {
+            return Err(ContractError::InvalidPrizeConfig {
+                reason: "denom can't change while the prize pool is funded".to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+pub fn update_prize_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    config: PrizeConfig,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+    validate_prize_config(deps.storage, &config)?;
+
+    PRIZE_CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_prize_config"))
+}
+
+pub fn fund_prize_pool(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info.clone(), Role::Treasurer)?;
+    let config = PRIZE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::PrizeConfigMissing {})?;
+
+    let amount = match info.funds.as_slice() {
+        [funds] if funds.denom == config.denom && !funds.amount.is_zero() => funds.amount,
+        _ => {
+            return Err(ContractError::InvalidPrizeFunds {
+                denom: config.denom,
+            })
+        }
+    };
+
+    let pool = PRIZE_POOL.may_load(deps.storage)?.unwrap_or_default() + amount;
+    PRIZE_POOL.save(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fund_prize_pool")
+        .add_attribute("prize_pool", pool))
+}
+
+/// Winners of a closed season which was not paid yet.
+fn season_winners(
+    storage: &dyn Storage,
+    season_id: u64,
+    ranks: usize,
+) -> Result<Vec<LeaderboardEntry>, ContractError> {
+    let season = load_season(storage, season_id)?;
+    if !season.closed {
+        return Err(ContractError::SeasonNotEnded {
+            season_id,
+            end: season.end,
+        });
+    }
+
+    if PRIZE_PAYOUTS.has(storage, season_id) {
+        return Err(ContractError::PrizesDistributed { season_id });
+    }
+
+    let winners = SEASON_RESULTS
+        .prefix(season_id)
+        .range(storage, None, None, Order::Ascending)
+        .take(ranks)
+        .map(|res| res.map(|(_, entry)| entry))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(winners)
+}
+
+/// Top ranks of the all time leaderboard, which can only be paid once.
+fn all_time_winners(
+    storage: &dyn Storage,
+    ranks: usize,
+) -> Result<Vec<LeaderboardEntry>, ContractError> {
+    if ALL_TIME_PRIZE_PAYOUTS.exists(storage) {
+        return Err(ContractError::AllTimePrizesDistributed {});
+    }
+
+    let (first_rank, page) = total_page(storage, None, ranks)?;
+
+    Ok(leaderboard_entries(first_rank, page))
+}
+
+pub fn distribute_prizes(
+    deps: DepsMut,
+    info: MessageInfo,
+    season_id: Option<u64>,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+    let config = PRIZE_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::PrizeConfigMissing {})?;
+
+    let winners = match season_id {
+        Some(season_id) => season_winners(deps.storage, season_id, config.shares.len())?,
+        None => all_time_winners(deps.storage, config.shares.len())?,
+    };
+
+    let pool = PRIZE_POOL.may_load(deps.storage)?.unwrap_or_default();
+    if pool.is_zero() {
+        return Err(ContractError::NoPrizes {});
+    }
+
+    let mut payouts = vec![];
+    let mut paid = Uint128::zero();
+    for (entry, share) in winners.into_iter().zip(config.shares.iter()) {
+        let amount = pool.multiply_ratio(*share, params::BASIS_POINTS);
+        if amount.is_zero() {
+            continue;
+        }
+
+        paid += amount;
+        payouts.push(PrizePayout {
+            rank: entry.rank,
+            address: entry.address,
+            amount: coin(amount.u128(), config.denom.clone()),
+        });
+    }
+
+    // Nothing is recorded, so the prizes can still be paid once the pool is funded.
+    if payouts.is_empty() {
+        return Err(ContractError::NoPrizes {});
+    }
+
+    match season_id {
+        Some(season_id) => PRIZE_PAYOUTS.save(deps.storage, season_id, &payouts)?,
+        None => ALL_TIME_PRIZE_PAYOUTS.save(deps.storage, &payouts)?,
+    }
+    PRIZE_POOL.save(deps.storage, &(pool - paid))?;
+
+    let messages = payouts.into_iter().map(|payout| BankMsg::Send {
+        to_address: payout.address,
+        amount: vec![payout.amount],
+    });
+
+    Ok(Response::new()
+        .add_attribute("action", "distribute_prizes")
+        .add_attribute(
+            "season_id",
+            season_id.map_or("all_time".to_string(), |id| id.to_string()),
+        )
+        .add_attribute("paid", paid)
+        .add_messages(messages))
+}
@@ -0,0 +1,230 @@
+use cosmwasm_std::{
+    ensure_eq, to_binary, BlockInfo, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, Storage,
+    WasmMsg,
+};
+use nois::{int_in_range, NoisCallback, ProxyExecuteMsg};
+
+use crate::{
+    helpers::mint_seeds,
+    msg::PausableAction,
+    pause::ensure_action_enabled,
+    state::{
+        FarmProfile, NoiseJob, PendingNoisJob, CROP_EVENTS_CONFIG, CROP_EVENT_ROLLS, FARMS,
+        NOIS_CONFIG, NOIS_JOBS, NOIS_JOB_LAST_ID, NOIS_PROXY,
+    },
+    ContractError,
+};
+
+fn next_job_id(store: &mut dyn Storage) -> Result<String, ContractError> {
+    let last_id = (NOIS_JOB_LAST_ID.may_load(store)?).unwrap_or(0);
+    let next_id = last_id + 1;
+    NOIS_JOB_LAST_ID.save(store, &next_id)?;
+
+    Ok(next_id.to_string())
+}
+
+/// Stores the job and returns its id with the message requesting randomness from the Nois proxy.
+/// The funds pay the proxy for the randomness, they can't be reclaimed if the job times out.
+pub fn request_randomness(
+    storage: &mut dyn Storage,
+    job: NoiseJob,
+    block: u64,
+    funds: Vec<Coin>,
+) -> Result<(String, CosmosMsg), ContractError> {
+    let nois_proxy = NOIS_PROXY
+        .may_load(storage)?
+        .ok_or(ContractError::NoisProxyMissing {})?;
+
+    let job_id = next_job_id(storage)?;
+    NOIS_JOBS.save(
+        storage,
+        &job_id,
+        &PendingNoisJob {
+            job,
+            created_at: block,
+            funds: funds.clone(),
+        },
+    )?;
+
+    let msg = WasmMsg::Execute {
+        contract_addr: nois_proxy.into(),
+        msg: to_binary(&ProxyExecuteMsg::GetNextRandomness {
+            job_id: job_id.clone(),
+        })?,
+        funds,
+    };
+
+    Ok((job_id, msg.into()))
+}
+
+fn run_job(
+    job: NoiseJob,
+    randomness: [u8; 32],
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+) -> Result<Response, ContractError> {
+    match job {
+        NoiseJob::MintSeeds {
+            plant,
+            recipient,
+            min_seeds,
+            max_seeds,
+        } => {
+            let seeds = int_in_range(randomness, min_seeds, max_seeds);
+            let messages = mint_seeds(plant, recipient, seeds, storage)?;
+
+            Ok(Response::new().add_messages(messages))
+        }
+        NoiseJob::CropEvents { owner } => {
+            // The farm could have been stopped while waiting for the randomness.
+            let mut farm = match FarmProfile::load_all(storage, &owner)? {
+                None => return Ok(Response::new()),
+                Some(farm) => farm,
+            };
+
+            let config = CROP_EVENTS_CONFIG.load(storage)?;
+            let now = farm.now(block);
+            let events = farm.roll_crop_events(randomness, &config, now);
+            farm.save(storage, &owner)?;
+
+            let attributes = events
+                .iter()
+                .map(|(x, y, event)| ("crop_event", format!("{}/{}/{}", x, y, event)));
+
+            Ok(Response::new().add_attributes(attributes))
+        }
+    }
+}
+
+/// Deterministic outcome used when the randomness never arrived.
+fn run_fallback_job(job: NoiseJob, storage: &dyn Storage) -> Result<Response, ContractError> {
+    match job {
+        NoiseJob::MintSeeds {
+            plant,
+            recipient,
+            min_seeds,
+            ..
+        } => {
+            let messages = mint_seeds(plant, recipient, min_seeds, storage)?;
+
+            Ok(Response::new().add_messages(messages))
+        }
+        // Without randomness nothing happens to the plants.
+        NoiseJob::CropEvents { .. } => Ok(Response::new()),
+    }
+}
+
+fn load_job(storage: &dyn Storage, job_id: &str) -> Result<PendingNoisJob, ContractError> {
+    NOIS_JOBS
+        .may_load(storage, job_id)?
+        .ok_or_else(|| ContractError::UnknownNoisJob {
+            job_id: job_id.to_string(),
+        })
+}
+
+fn load_expired_job(
+    storage: &dyn Storage,
+    job_id: &str,
+    block: u64,
+) -> Result<PendingNoisJob, ContractError> {
+    let job = load_job(storage, job_id)?;
+    let config = NOIS_CONFIG.load(storage)?;
+
+    let expires_at = job.created_at.saturating_add(config.job_timeout);
+    if block < expires_at {
+        return Err(ContractError::NoisJobNotExpired {
+            job_id: job_id.to_string(),
+            expires_at,
+        });
+    }
+
+    Ok(job)
+}
+
+pub fn nois_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    callback: NoisCallback,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Nois)?;
+
+    let proxy = NOIS_PROXY
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NoisProxyMissing {})?;
+    ensure_eq!(info.sender, proxy, ContractError::Unauthorized {});
+
+    let NoisCallback {
+        job_id, randomness, ..
+    } = callback;
+
+    let randomness: [u8; 32] = randomness
+        .to_array()
+        .map_err(|_| ContractError::InvalidRandomness {})?;
+
+    let pending = load_job(deps.storage, &job_id)?;
+    NOIS_JOBS.remove(deps.storage, &job_id);
+    run_job(pending.job, randomness, deps.storage, &env.block)
+}
+
+pub fn settle_job(deps: DepsMut, env: Env, job_id: String) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Nois)?;
+
+    let pending = load_expired_job(deps.storage, &job_id, env.block.height)?;
+    NOIS_JOBS.remove(deps.storage, &job_id);
+    let res = run_fallback_job(pending.job, deps.storage)?;
+
+    Ok(res
+        .add_attribute("action", "settle_nois_job")
+        .add_attribute("job_id", job_id))
+}
+
+pub fn retry_job(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    job_id: String,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Nois)?;
+
+    let pending = load_expired_job(deps.storage, &job_id, env.block.height)?;
+    NOIS_JOBS.remove(deps.storage, &job_id);
+    let (new_job_id, msg) =
+        request_randomness(deps.storage, pending.job, env.block.height, info.funds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "retry_nois_job")
+        .add_attribute("job_id", job_id)
+        .add_attribute("new_job_id", new_job_id)
+        .add_message(msg))
+}
+
+pub fn roll_crop_events(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let sender = info.sender.to_string();
+    if !FARMS.has(deps.storage, sender.as_str()) {
+        return Err(ContractError::NoFarm { address: sender });
+    }
+
+    let config = CROP_EVENTS_CONFIG.load(deps.storage)?;
+    if let Some(last_roll) = CROP_EVENT_ROLLS.may_load(deps.storage, sender.as_str())? {
+        let available_at = last_roll.saturating_add(config.cooldown);
+        if env.block.height < available_at {
+            return Err(ContractError::CropEventsCooldown { available_at });
+        }
+    }
+    CROP_EVENT_ROLLS.save(deps.storage, sender.as_str(), &env.block.height)?;
+
+    let job = NoiseJob::CropEvents {
+        owner: sender.clone(),
+    };
+    let (job_id, msg) = request_randomness(deps.storage, job, env.block.height, info.funds)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "roll_crop_events")
+        .add_attribute("job_id", job_id)
+        .add_message(msg))
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{DepsMut, Env, Response};
+
+use crate::{
+    actions::load_farm,
+    farm::{Fertilizer, SeedNft},
+    helpers::burn_nft_msg,
+    ContractError,
+};
+
+pub fn fertilize(
+    deps: DepsMut,
+    env: Env,
+    sender: String,
+    nft: SeedNft,
+    fertilizer: Fertilizer,
+    x: u8,
+    y: u8,
+) -> Result<Response, ContractError> {
+    let mut farm = load_farm(deps.storage, &sender, &[(x, y)])?;
+
+    let now = farm.now(&env.block);
+    farm.fertilize(x.into(), y.into(), fertilizer, now)?;
+    farm.save(deps.storage, &sender)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "fertilize")
+        .add_attribute("token_id", nft.token_id.clone())
+        .add_message(burn_nft_msg(&nft)?))
+}
@@ -1,9 +1,11 @@
-use cosmwasm_std::{from_binary, DepsMut, Env, MessageInfo, Response};
+use cosmwasm_std::{from_binary, Coin, Deps, DepsMut, Env, MessageInfo, Response};
 
+mod fertilize;
 mod seed;
 
 use cw721::Cw721ReceiveMsg;
 use cw721_base::QueryMsg as Cw721QueryMsg;
+use fertilize::fertilize;
 use komple_framework_metadata_module::msg::{MetadataResponse, QueryMsg as KompleMetadataQueryMsg};
 use komple_framework_mint_module::msg::{CollectionsResponse, QueryMsg as KompleMintQueryMsg};
 use komple_framework_token_module::msg::QueryMsg as KompleTokenQueryMsg;
@@ -11,23 +13,34 @@ use komple_framework_types::{modules::token::SubModules, shared::query::Response
 use seed::seed;
 
 use crate::{
-    farm::KomplePlant, helpers::throw_err, msg::Cw721HookMsg, state::INFORMATION, ContractError,
+    farm::{Fertilizer, KomplePlant, PlantType, SeedNft},
+    helpers::transfer_nft_msg,
+    market::create_listing,
+    msg::{Cw721HookMsg, PausableAction},
+    pause::ensure_action_enabled,
+    state::{load_plant_config, ListedItem, INFORMATION},
+    ContractError,
 };
 
-pub fn receive(
-    deps: DepsMut,
-    env: Env,
-    info: MessageInfo,
-    msg: Cw721ReceiveMsg,
-) -> Result<Response, ContractError> {
+struct KompleToken {
+    collection: CollectionsResponse,
+    metadata: MetadataResponse,
+}
+
+/// Checks that the NFT comes from a Komple collection and loads its metadata.
+fn komple_token(
+    deps: Deps,
+    info: &MessageInfo,
+    token_id: &str,
+) -> Result<KompleToken, ContractError> {
     let config = INFORMATION.load(deps.storage)?;
 
-    if config.komple_mint_addr.is_none() {
-        return Err(throw_err("Komple mint address not provided"));
-    }
+    let komple_mint_addr = config
+        .komple_mint_addr
+        .ok_or(ContractError::KompleMintMissing {})?;
 
     let collections: ResponseWrapper<Vec<CollectionsResponse>> = deps.querier.query_wasm_smart(
-        config.komple_mint_addr.unwrap(),
+        komple_mint_addr,
         &KompleMintQueryMsg::Collections {
             blacklist: false,
             start_after: None,
@@ -35,13 +48,13 @@ pub fn receive(
         },
     )?;
 
-    let collection = collections.data.iter().find(|c| info.sender.eq(&c.address));
-
-    if collection.is_none() {
-        return Err(throw_err("Unauthorized collection"));
-    }
-
-    let collection = collection.unwrap();
+    let collection = collections
+        .data
+        .into_iter()
+        .find(|c| info.sender.eq(&c.address))
+        .ok_or_else(|| ContractError::UnauthorizedCollection {
+            address: info.sender.to_string(),
+        })?;
 
     let submodules: ResponseWrapper<SubModules> = deps.querier.query_wasm_smart(
         &collection.address,
@@ -50,73 +63,184 @@ pub fn receive(
         },
     )?;
 
-    if submodules.data.metadata.is_none() {
-        return Err(throw_err("Missing Komple metadata submodule"));
-    }
+    let metadata_addr = submodules
+        .data
+        .metadata
+        .ok_or(ContractError::KompleMetadataMissing {})?;
+
+    let token_id = token_id
+        .parse::<u32>()
+        .map_err(|_| ContractError::InvalidTokenId {
+            token_id: token_id.to_string(),
+        })?;
 
     let metadata: ResponseWrapper<MetadataResponse> = deps.querier.query_wasm_smart(
-        submodules.data.metadata.unwrap(),
-        &KompleMetadataQueryMsg::Metadata {
-            token_id: msg.token_id.parse::<u32>().unwrap(),
-        },
+        metadata_addr,
+        &KompleMetadataQueryMsg::Metadata { token_id },
     )?;
 
-    let plant_type = metadata
-        .data
+    Ok(KompleToken {
+        collection,
+        metadata: metadata.data,
+    })
+}
+
+fn token_attribute<'a>(token: &'a KompleToken, trait_type: &str) -> Result<&'a str, ContractError> {
+    token
+        .metadata
         .metadata
         .attributes
         .iter()
-        .find(|a| a.trait_type == "type");
+        .find(|a| a.trait_type == trait_type)
+        .map(|a| a.value.as_str())
+        .ok_or_else(|| ContractError::MetadataAttributeMissing {
+            trait_type: trait_type.to_string(),
+        })
+}
 
-    if plant_type.is_none() {
-        return Err(throw_err("Missing metadata type"));
-    }
+/// Plant type from the "type" attribute of the token metadata.
+fn token_plant_type(token: &KompleToken) -> Result<PlantType, ContractError> {
+    token_attribute(token, "type")?.parse()
+}
+
+fn seed_from_nft(
+    deps: DepsMut,
+    env: Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+    x: u8,
+    y: u8,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Seed)?;
 
-    let plant_type = plant_type.unwrap().value.parse()?;
+    let token = komple_token(deps.as_ref(), info, &msg.token_id)?;
+    let plant_type = token_plant_type(&token)?;
+    let plant_config = load_plant_config(deps.storage, &plant_type)?;
 
     let komple = KomplePlant {
-        metadata_id: metadata.data.metadata_id,
-        collection_id: collection.collection_id,
+        metadata_id: token.metadata.metadata_id,
+        collection_id: token.collection.collection_id,
     };
 
+    seed(
+        deps,
+        env,
+        msg.sender,
+        SeedNft {
+            collection: info.sender.to_string(),
+            token_id: msg.token_id,
+        },
+        plant_type,
+        plant_config,
+        komple,
+        x,
+        y,
+    )
+}
+
+fn fertilize_from_nft(
+    deps: DepsMut,
+    env: Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+    x: u8,
+    y: u8,
+) -> Result<Response, ContractError> {
+    let token = komple_token(deps.as_ref(), info, &msg.token_id)?;
+    let fertilizer: Fertilizer = token_attribute(&token, "fertilizer")?.parse()?;
+
+    fertilize(
+        deps,
+        env,
+        msg.sender,
+        SeedNft {
+            collection: info.sender.to_string(),
+            token_id: msg.token_id,
+        },
+        fertilizer,
+        x,
+        y,
+    )
+}
+
+fn list_nft(
+    deps: DepsMut,
+    env: Env,
+    info: &MessageInfo,
+    msg: Cw721ReceiveMsg,
+    price: Coin,
+) -> Result<Response, ContractError> {
+    let token = komple_token(deps.as_ref(), info, &msg.token_id)?;
+    let plant_type = token_plant_type(&token)?;
+
+    create_listing(
+        deps.storage,
+        msg.sender,
+        plant_type,
+        ListedItem::SeedNft(SeedNft {
+            collection: info.sender.to_string(),
+            token_id: msg.token_id,
+        }),
+        price,
+        env.block.height,
+    )
+}
+
+pub fn receive(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
     match from_binary(&msg.msg)? {
-        Cw721HookMsg::Seed { x, y } => seed(
-            deps,
-            env,
-            msg.sender,
-            msg.token_id,
-            plant_type,
-            komple,
-            x,
-            y,
-        ),
+        Cw721HookMsg::Seed { x, y } => seed_from_nft(deps, env, &info, msg, x, y),
+        Cw721HookMsg::Fertilize { x, y } => fertilize_from_nft(deps, env, &info, msg, x, y),
+        Cw721HookMsg::List { price } => list_nft(deps, env, &info, msg, price),
+        Cw721HookMsg::TrySeed { x, y } => {
+            let nft = SeedNft {
+                collection: info.sender.to_string(),
+                token_id: msg.token_id.clone(),
+            };
+            let owner = msg.sender.clone();
+
+            // Seeding only writes to storage once every check passed,
+            // so a failed attempt leaves no state behind.
+            match seed_from_nft(deps.branch(), env, &info, msg, x, y) {
+                Ok(res) => Ok(res),
+                Err(err) => Ok(Response::new()
+                    .add_attribute("action", "refund_seed")
+                    .add_attribute("reason", err.to_string())
+                    .add_message(transfer_nft_msg(&nft, owner)?)),
+            }
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use cosmwasm_std::{testing::mock_info, to_binary, SystemResult, WasmQuery};
-    use cw721::Cw721ReceiveMsg;
-    use komple_framework_metadata_module::{
-        msg::MetadataResponse,
-        state::{MetaInfo, Metadata, Trait},
-    };
+    use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
     use komple_framework_mint_module::msg::CollectionsResponse;
-    use komple_framework_types::{modules::token::SubModules, shared::query::ResponseWrapper};
+    use komple_framework_types::shared::query::ResponseWrapper;
 
     use crate::{
         contract::execute,
         msg::{Cw721HookMsg, ExecuteMsg, InstantiateMsg},
-        tests::{general_handle_wasm_query, get_komple_addrs, init_farm, setup_test, till},
+        tests::{
+            cw721_msg, general_handle_wasm_query, get_farm_nfts, get_komple_addrs, init_farm,
+            komple_seed_wasm_query, setup_test, till,
+        },
+        ContractError,
     };
 
     #[test]
-    #[should_panic(expected = "Unauthorized collection")]
+    #[should_panic(expected = "UnauthorizedCollection")]
     fn unauthorized_collection() {
         let (mut deps, env) = setup_test(Some(InstantiateMsg {
             admin: None,
             komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
             nois_proxy: None,
+            clock_mode: None,
         }));
 
         deps.querier
@@ -158,63 +282,12 @@ mod test {
             admin: None,
             komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
             nois_proxy: None,
+            clock_mode: None,
         }));
 
-        deps.querier
-            .update_wasm(move |wasm_query| match wasm_query {
-                WasmQuery::Smart {
-                    contract_addr,
-                    msg: _msg,
-                } if *contract_addr == collection_addr => SystemResult::Ok(
-                    to_binary(&ResponseWrapper::new(
-                        "sub_modules",
-                        SubModules {
-                            metadata: Some(get_komple_addrs().metadata),
-                            whitelist: None,
-                        },
-                    ))
-                    .into(),
-                ),
-                WasmQuery::Smart {
-                    contract_addr,
-                    msg: _msg,
-                } if *contract_addr == get_komple_addrs().metadata => SystemResult::Ok(
-                    to_binary(&ResponseWrapper::new(
-                        "metadata",
-                        MetadataResponse {
-                            metadata_id: 1,
-                            metadata: Metadata {
-                                attributes: vec![Trait {
-                                    trait_type: "type".into(),
-                                    value: "wheat".into(),
-                                }],
-                                meta_info: MetaInfo {
-                                    image: None,
-                                    external_url: None,
-                                    description: None,
-                                    animation_url: None,
-                                    youtube_url: None,
-                                },
-                            },
-                        },
-                    ))
-                    .into(),
-                ),
-                WasmQuery::Smart {
-                    contract_addr,
-                    msg: _msg,
-                } if *contract_addr == get_komple_addrs().mint => SystemResult::Ok(
-                    to_binary(&ResponseWrapper::new(
-                        "collections",
-                        vec![CollectionsResponse {
-                            address: collection_addr.to_string(),
-                            collection_id: 1,
-                        }],
-                    ))
-                    .into(),
-                ),
-                _ => general_handle_wasm_query(wasm_query),
-            });
+        deps.querier.update_wasm(move |wasm_query| {
+            komple_seed_wasm_query(wasm_query, collection_addr, "wheat")
+        });
 
         let auth_info = mock_info(collection_addr, &[]);
         let nft_owner = "nft_owner";
@@ -231,4 +304,87 @@ mod test {
 
         assert!(res.is_ok());
     }
+
+    #[test]
+    fn invalid_token_id() {
+        let collection_addr = "collection_addr";
+        let (mut deps, env) = setup_test(Some(InstantiateMsg {
+            admin: None,
+            komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+            nois_proxy: None,
+            clock_mode: None,
+        }));
+
+        deps.querier.update_wasm(move |wasm_query| {
+            komple_seed_wasm_query(wasm_query, collection_addr, "wheat")
+        });
+
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: "nft_owner".to_string(),
+            token_id: "seed-1".to_string(),
+            msg: to_binary(&Cw721HookMsg::Seed { x: 0, y: 0 }).unwrap(),
+        });
+
+        let err = execute(deps.as_mut(), env, mock_info(collection_addr, &[]), msg).unwrap_err();
+
+        assert_eq!(
+            err,
+            ContractError::InvalidTokenId {
+                token_id: "seed-1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn try_seed_refunds_nft() {
+        let collection_addr = "collection_addr";
+        let (mut deps, env) = setup_test(Some(InstantiateMsg {
+            admin: None,
+            komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+            nois_proxy: None,
+            clock_mode: None,
+        }));
+
+        deps.querier.update_wasm(move |wasm_query| {
+            komple_seed_wasm_query(wasm_query, collection_addr, "wheat")
+        });
+
+        let nft_owner = "nft_owner";
+        init_farm(nft_owner, deps.as_mut());
+
+        // Plot [0, 0] is still meadow.
+        let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: nft_owner.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&Cw721HookMsg::TrySeed { x: 0, y: 0 }).unwrap(),
+        });
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(collection_addr, &[]),
+            msg.clone(),
+        )
+        .unwrap();
+
+        assert_eq!(res.attributes[0].value, "refund_seed");
+        assert_eq!(
+            res.attributes[1].value,
+            ContractError::PlotNotField { x: 0, y: 0 }.to_string()
+        );
+        assert_eq!(
+            cw721_msg(&res.messages[0].msg, collection_addr),
+            Cw721ExecuteMsg::TransferNft {
+                recipient: nft_owner.to_string(),
+                token_id: "1".to_string()
+            }
+        );
+        assert!(get_farm_nfts(nft_owner, deps.as_ref()).is_empty());
+
+        till(nft_owner, 0, 0, deps.as_mut());
+        let res = execute(deps.as_mut(), env, mock_info(collection_addr, &[]), msg).unwrap();
+
+        assert_eq!(res.attributes[0].value, "seed");
+        assert!(res.messages.is_empty());
+        assert_eq!(get_farm_nfts(nft_owner, deps.as_ref()).len(), 1);
+    }
 }
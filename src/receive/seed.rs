@@ -1,9 +1,8 @@
 use cosmwasm_std::{DepsMut, Env, Response};
 
 use crate::{
-    farm::{KomplePlant, PlantType, SlotType},
-    helpers::throw_err,
-    state::FARM_PROFILES,
+    actions::load_farm,
+    farm::{KomplePlant, PlantConfig, PlantType, SeedNft},
     ContractError,
 };
 
@@ -12,38 +11,30 @@ pub fn seed(
     deps: DepsMut,
     env: Env,
     sender: String,
-    _token_id: String,
+    seed_nft: SeedNft,
     plant_type: PlantType,
+    plant_config: PlantConfig,
     komple: KomplePlant,
     x: u8,
     y: u8,
 ) -> Result<Response, ContractError> {
-    let farm = FARM_PROFILES.may_load(deps.storage, sender.as_str())?;
-
-    if farm.is_none() {
-        return Err(ContractError::PlayerDoesNotExist { address: sender });
-    }
-
-    let mut farm = farm.unwrap();
-
-    let plot = farm.get_plot(x.into(), y.into());
-    if plot.get_real_type(env.block.height) != SlotType::Field || plot.plant.is_some() {
-        return Err(throw_err(&format!(
-            "Plot [{}, {}] must be an empty field to plant a seed.",
-            x, y
-        )));
-    }
+    let mut farm = load_farm(deps.storage, &sender, &[(x, y)])?;
+    let now = farm.now(&env.block);
 
     farm.plant_seed(
         x.into(),
         y.into(),
         &plant_type,
+        &plant_config,
         Some(komple),
-        env.block.height,
-    );
-    FARM_PROFILES.save(deps.storage, sender.as_str(), &farm)?;
-
-    Ok(Response::new().add_attribute("action", "seed"))
+        Some(seed_nft.clone()),
+        now,
+    )?;
+    farm.save(deps.storage, &sender)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "seed")
+        .add_attribute("token_id", seed_nft.token_id))
 }
 
 #[cfg(test)]
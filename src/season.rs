@@ -0,0 +1,167 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Order, Response, StdResult, Storage};
+use cw_storage_plus::Bound;
+
+use crate::{
+    helpers::role_error_check,
+    leaderboard::{leaderboard_entries, season_page},
+    msg::{LeaderboardEntry, Role, Season},
+    params,
+    state::{season_points, SeasonPoints, SEASONS, SEASON_LAST_ID, SEASON_RESULTS},
+    ContractError,
+};
+
+pub fn load_season(storage: &dyn Storage, season_id: u64) -> Result<Season, ContractError> {
+    SEASONS
+        .may_load(storage, season_id)?
+        .ok_or(ContractError::UnknownSeason { season_id })
+}
+
+/// Season collecting points at the given block.
+pub fn active_season(storage: &dyn Storage, block: u64) -> StdResult<Option<Season>> {
+    // Seasons never overlap, so only seasons ending after the block need to be checked.
+    for season in SEASONS.range(storage, None, None, Order::Descending) {
+        let (_, season) = season?;
+        if season.end <= block {
+            break;
+        }
+        if season.start <= block && !season.closed {
+            return Ok(Some(season));
+        }
+    }
+
+    Ok(None)
+}
+
+pub fn add_season_points(
+    storage: &mut dyn Storage,
+    addr: &str,
+    amount: u64,
+    block: u64,
+) -> StdResult<()> {
+    let season = match active_season(storage, block)? {
+        None => return Ok(()),
+        Some(season) => season,
+    };
+
+    let mut pts = season_points()
+        .may_load(storage, (season.id, addr))?
+        .unwrap_or_else(|| SeasonPoints {
+            season_id: season.id,
+            addr: addr.to_string(),
+            total: 0,
+        });
+    pts.total += amount;
+
+    season_points().save(storage, (season.id, addr), &pts)
+}
+
+pub fn create_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::GameMaster)?;
+
+    if start >= end {
+        return Err(ContractError::InvalidSeason {
+            reason: "start must be before end".to_string(),
+        });
+    }
+
+    if start < env.block.height {
+        return Err(ContractError::InvalidSeason {
+            reason: "start must not be in the past".to_string(),
+        });
+    }
+
+    let last_id = SEASON_LAST_ID.may_load(deps.storage)?.unwrap_or(0);
+    if let Some(last_season) = SEASONS.may_load(deps.storage, last_id)? {
+        if start < last_season.end {
+            return Err(ContractError::InvalidSeason {
+                reason: format!(
+                    "season {} ends at block {}",
+                    last_season.id, last_season.end
+                ),
+            });
+        }
+    }
+
+    let season = Season {
+        id: last_id + 1,
+        start,
+        end,
+        closed: false,
+    };
+    SEASON_LAST_ID.save(deps.storage, &season.id)?;
+    SEASONS.save(deps.storage, season.id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_season")
+        .add_attribute("season_id", season.id.to_string()))
+}
+
+pub fn close_season(deps: DepsMut, env: Env, season_id: u64) -> Result<Response, ContractError> {
+    let mut season = load_season(deps.storage, season_id)?;
+
+    if season.closed {
+        return Err(ContractError::SeasonClosed { season_id });
+    }
+
+    if env.block.height < season.end {
+        return Err(ContractError::SeasonNotEnded {
+            season_id,
+            end: season.end,
+        });
+    }
+
+    let (first_rank, page) =
+        season_page(deps.storage, season_id, None, params::SEASON_RESULTS_SIZE)?;
+    for entry in leaderboard_entries(first_rank, page) {
+        SEASON_RESULTS.save(deps.storage, (season_id, entry.rank), &entry)?;
+    }
+
+    season.closed = true;
+    SEASONS.save(deps.storage, season_id, &season)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close_season")
+        .add_attribute("season_id", season_id.to_string()))
+}
+
+pub fn query_seasons(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Season>> {
+    let limit = limit
+        .unwrap_or(params::DEFAULT_LIMIT)
+        .min(params::MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    SEASONS
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, season)| season))
+        .collect()
+}
+
+pub fn query_season_results(
+    storage: &dyn Storage,
+    season_id: u64,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<LeaderboardEntry>> {
+    let limit = limit
+        .unwrap_or(params::DEFAULT_LIMIT)
+        .min(params::MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    SEASON_RESULTS
+        .prefix(season_id)
+        .range(storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|res| res.map(|(_, entry)| entry))
+        .collect()
+}
@@ -0,0 +1,87 @@
+use cosmwasm_std::{BankMsg, Coin, DepsMut, MessageInfo, Response, Uint128};
+
+use crate::{
+    farm::PlantType,
+    helpers::{mint_seeds, role_error_check, treasury},
+    msg::{PausableAction, Role, SeedPrice},
+    params,
+    pause::ensure_action_enabled,
+    state::{load_plant_config, SEED_PRICES},
+    ContractError,
+};
+
+pub fn set_seed_price(
+    deps: DepsMut,
+    info: MessageInfo,
+    plant_type: String,
+    price: Option<SeedPrice>,
+) -> Result<Response, ContractError> {
+    role_error_check(deps.as_ref(), info, Role::Treasurer)?;
+    let plant_type: PlantType = plant_type.parse()?;
+    load_plant_config(deps.storage, &plant_type)?;
+    if matches!(&price, Some(price) if price.price.amount.is_zero()) {
+        return Err(ContractError::InvalidPrice {});
+    }
+
+    match price {
+        None => SEED_PRICES.remove(deps.storage, plant_type.as_str()),
+        Some(price) => SEED_PRICES.save(deps.storage, plant_type.as_str(), &price)?,
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_seed_price")
+        .add_attribute("plant_type", plant_type.to_string()))
+}
+
+pub fn buy_seeds(
+    deps: DepsMut,
+    info: MessageInfo,
+    plant_type: String,
+    amount: u32,
+) -> Result<Response, ContractError> {
+    ensure_action_enabled(deps.storage, PausableAction::Shop)?;
+
+    let plant_type: PlantType = plant_type.parse()?;
+    let seed_price = SEED_PRICES
+        .may_load(deps.storage, plant_type.as_str())?
+        .ok_or_else(|| ContractError::SeedsNotForSale {
+            plant_type: plant_type.to_string(),
+        })?;
+
+    if amount == 0 || amount > params::MAX_SEEDS_PURCHASE {
+        return Err(ContractError::InvalidSeedAmount {
+            max_amount: params::MAX_SEEDS_PURCHASE,
+        });
+    }
+
+    let required = Coin {
+        amount: seed_price.price.amount.checked_mul(Uint128::from(amount))?,
+        denom: seed_price.price.denom,
+    };
+    if info.funds != [required.clone()] {
+        return Err(ContractError::InvalidFunds {
+            required: Some(required),
+        });
+    }
+
+    let treasury = treasury(deps.storage)?;
+    let mut messages = mint_seeds(
+        seed_price.komple,
+        info.sender.into_string(),
+        amount,
+        deps.storage,
+    )?;
+    messages.push(
+        BankMsg::Send {
+            to_address: treasury,
+            amount: info.funds,
+        }
+        .into(),
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "buy_seeds")
+        .add_attribute("plant_type", plant_type.to_string())
+        .add_attribute("amount", amount.to_string())
+        .add_messages(messages))
+}
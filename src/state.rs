@@ -1,54 +1,170 @@
-use std::collections::HashMap;
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
+use cosmwasm_std::{Addr, BlockInfo, Coin, Order, StdError, StdResult, Storage, Uint128};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
+use nois::{int_in_range, sub_randomness_with_key};
 
 use crate::{
-    farm::{KomplePlant, Plant, PlantType, Slot, SlotType},
-    helpers::throw_err,
-    msg::ContractInformation,
+    error::{FertilizeBlockReason, WaterBlockReason},
+    farm::{
+        ClockMode, CropEvent, Fertilizer, KomplePlant, Plant, PlantConfig, PlantType, SeedNft,
+        Slot, SlotType,
+    },
+    msg::{
+        ContractInformation, CropEventsConfig, ExpansionConfig, LeaderboardEntry, MarketConfig,
+        NoisConfig, PauseStatus, PrizeConfig, PrizePayout, Role, Season, SeedPrice,
+    },
     params, ContractError,
 };
 
-fn plant_dto(plant: &Option<Plant>, block: u64) -> Option<PlantDto> {
+fn plant_dto(plant: &Option<Plant>, block: u64, irrigated_since: Option<u64>) -> Option<PlantDto> {
     plant.as_ref().map(|plant| PlantDto {
         created_at: plant.created_at,
         growth_period: plant.growth_period,
         komple: plant.komple.clone(),
         stages: plant.stages,
         r#type: plant.r#type.clone(),
-        watered_at: plant.watered_at.clone(),
-        can_harvest: plant.can_harvest(block),
-        can_water: plant.can_water(block),
+        watered_at: vec![plant.last_watered_at; plant.watered.min(plant.stages) as usize],
+        watered_stages: plant.watered,
+        last_watered_at: plant.last_watered_at,
+        watering_tolerance: plant.watering_tolerance,
+        seed_nft: plant.seed_nft.clone(),
+        effects: plant.effects.clone(),
+        fertilizers: plant.fertilizers.clone(),
+        can_harvest: plant.can_harvest(block, irrigated_since),
+        can_water: plant.can_water(block, irrigated_since),
         current_stage: plant.get_current_stage(block),
-        is_dead: plant.is_dead(block),
+        is_dead: plant.is_dead(block, irrigated_since),
     })
 }
 
-fn slot_dto(slot: &Slot, block: u64) -> SlotDto {
+fn slot_dto(slot: &Slot, block: u64, clock: &ClockMode, irrigated_since: Option<u64>) -> SlotDto {
     SlotDto {
-        plant: plant_dto(&slot.plant, block),
-        r#type: slot.get_real_type(block),
-        can_till: slot.can_till(block),
+        plant: plant_dto(&slot.plant, block, irrigated_since),
+        r#type: slot.get_real_type(block, clock),
+        can_till: slot.can_till(block, clock, irrigated_since),
+        irrigated: irrigated_since.is_some(),
         created_at: slot.created_at,
     }
 }
 
+/// Plots of a farm, used to set up a farm and by the legacy single value storage.
 #[cw_serde]
+pub struct FarmLayout {
+    pub plots: Vec<Vec<Slot>>,
+}
+
+impl FarmLayout {
+    /// Checks a farm set up by the admin, no timer may lie after `now`.
+    pub fn validate(&self, storage: &dyn Storage, now: u64) -> Result<(), ContractError> {
+        let size = self.plots.len();
+        if size == 0 || size > usize::from(u8::MAX) {
+            return Err(ContractError::InvalidFarm {
+                reason: format!("size must be between 1 and {}", u8::MAX),
+            });
+        }
+
+        for (x, row) in self.plots.iter().enumerate() {
+            if row.len() != size {
+                return Err(ContractError::InvalidFarm {
+                    reason: format!("row {} must have {} plots", x, size),
+                });
+            }
+
+            for (y, slot) in row.iter().enumerate() {
+                validate_slot(storage, slot, x, y, now)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks a plot set up or patched by the admin, no timer may lie after `now`.
+pub fn validate_slot(
+    storage: &dyn Storage,
+    slot: &Slot,
+    x: usize,
+    y: usize,
+    now: u64,
+) -> Result<(), ContractError> {
+    let invalid = |reason: &str| ContractError::InvalidFarm {
+        reason: format!("plot [{}, {}] {}", x, y, reason),
+    };
+
+    if slot.created_at > now {
+        return Err(invalid("is created in the future"));
+    }
+
+    let plant = match &slot.plant {
+        None => return Ok(()),
+        Some(plant) => plant,
+    };
+    load_plant_config(storage, &plant.r#type)?;
+
+    if slot.r#type != SlotType::Field {
+        return Err(invalid("can only grow a plant on a field"));
+    }
+
+    // Only seeding through `ReceiveNft` may put a seed NFT held by the contract on a plot.
+    if plant.seed_nft.is_some() {
+        return Err(invalid("has a plant with a seed NFT"));
+    }
+
+    let watered_in_future = plant.watered_at.iter().any(|block| *block > now);
+    if plant.created_at > now || plant.last_watered_at > now || watered_in_future {
+        return Err(invalid("has a plant with a timer in the future"));
+    }
+
+    if plant.stages == 0 || plant.growth_period == 0 {
+        return Err(invalid("has a plant without stages or growth period"));
+    }
+
+    if plant.stages > params::MAX_PLANT_STAGES {
+        return Err(invalid("has a plant with too many stages"));
+    }
+
+    let watered = plant.watered.max(plant.watered_at.len() as u64);
+    if watered > plant.stages {
+        return Err(invalid("has a plant watered more often than it has stages"));
+    }
+
+    Ok(())
+}
+
+/// Farm header, the slots are stored per plot in `FARM_PLOTS`.
+#[cw_serde]
+pub struct FarmInfo {
+    pub size: u8,
+}
+
+/// Plots of a farm loaded from storage, only the changed plots are written back.
+#[derive(Clone, Debug, PartialEq)]
 pub struct FarmProfile {
-    plots: Vec<Vec<Slot>>,
+    size: usize,
+    clock: ClockMode,
+    plots: BTreeMap<(usize, usize), Slot>,
+    changed: BTreeSet<(usize, usize)>,
 }
 
-pub fn farm_profile_dto(farm_profile: &Option<FarmProfile>, block: u64) -> Option<FarmProfileDto> {
-    farm_profile.as_ref().map(|farm_profile| FarmProfileDto {
-        plots: farm_profile
-            .plots
-            .iter()
-            .map(|rows| rows.iter().map(|slot| slot_dto(slot, block)).collect())
+pub fn farm_profile_dto(farm_profile: &FarmProfile, block: u64) -> FarmProfileDto {
+    FarmProfileDto {
+        plots: (0..farm_profile.size)
+            .map(|x| {
+                (0..farm_profile.size)
+                    .filter_map(|y| {
+                        let slot = farm_profile.plots.get(&(x, y))?;
+                        let irrigated_since = farm_profile.irrigated_since(x, y);
+                        Some(slot_dto(slot, block, &farm_profile.clock, irrigated_since))
+                    })
+                    .collect()
+            })
             .collect(),
+        size: farm_profile.get_size(),
         blocks: block,
-    })
+        clock_mode: farm_profile.clock.clone(),
+    }
 }
 
 #[cw_serde]
@@ -57,8 +173,15 @@ pub struct PlantDto {
     pub stages: u64,
     pub growth_period: u64,
     pub created_at: u64,
+    /// Deprecated, the last watering height once per watered stage. Use `watered_stages`.
     pub watered_at: Vec<u64>,
+    pub watered_stages: u64,
+    pub last_watered_at: u64,
+    pub watering_tolerance: u64,
     pub komple: Option<KomplePlant>,
+    pub seed_nft: Option<SeedNft>,
+    pub effects: Vec<CropEvent>,
+    pub fertilizers: Vec<Fertilizer>,
     pub can_water: bool,
     pub can_harvest: bool,
     pub current_stage: u64,
@@ -70,13 +193,26 @@ pub struct SlotDto {
     pub r#type: SlotType,
     pub plant: Option<PlantDto>,
     pub can_till: bool,
+    /// A neighbouring sprinkler waters the plot.
+    pub irrigated: bool,
     pub created_at: u64,
 }
 
 #[cw_serde]
 pub struct FarmProfileDto {
-    plots: Vec<Vec<SlotDto>>,
-    blocks: u64,
+    pub plots: Vec<Vec<SlotDto>>,
+    pub size: usize,
+    /// Current time of the farm clock, the timers of the plots use the same unit.
+    pub blocks: u64,
+    pub clock_mode: ClockMode,
+}
+
+fn default_min_seeds() -> u32 {
+    params::MIN_SEEDS
+}
+
+fn default_max_seeds() -> u32 {
+    params::MAX_SEEDS
 }
 
 #[cw_serde]
@@ -84,26 +220,193 @@ pub enum NoiseJob {
     MintSeeds {
         plant: KomplePlant,
         recipient: String,
+        #[serde(default = "default_min_seeds")]
+        min_seeds: u32,
+        #[serde(default = "default_max_seeds")]
+        max_seeds: u32,
+    },
+    CropEvents {
+        owner: String,
     },
 }
 
-pub const FARM_PROFILES: Map<&str, FarmProfile> = Map::new("farm_profiles");
+#[cw_serde]
+pub struct PendingNoisJob {
+    pub job: NoiseJob,
+    pub created_at: u64,
+    /// Funds forwarded to the Nois proxy to pay for the randomness, set again on a retry.
+    #[serde(default)]
+    pub funds: Vec<Coin>,
+}
+
+/// Jobs stored before they were tracked with their creation height.
+const LEGACY_NOIS_JOBS: Map<&str, NoiseJob> = Map::new("nois_jobs");
+
+pub fn migrate_nois_jobs(storage: &mut dyn Storage, block: u64) -> StdResult<()> {
+    let legacy_jobs: Vec<(String, NoiseJob)> = LEGACY_NOIS_JOBS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    for (job_id, job) in legacy_jobs {
+        LEGACY_NOIS_JOBS.remove(storage, &job_id);
+        NOIS_JOBS.save(
+            storage,
+            &job_id,
+            &PendingNoisJob {
+                job,
+                created_at: block,
+                funds: vec![],
+            },
+        )?;
+    }
+
+    Ok(())
+}
+
+pub const CLOCK_MODE: Item<ClockMode> = Item::new("clock_mode");
+pub const FARMS: Map<&str, FarmInfo> = Map::new("farms");
+pub const FARM_PLOTS: Map<(&str, u8, u8), Slot> = Map::new("farm_plots");
+/// Farms stored as a single value before the plots were stored separately.
+const LEGACY_FARM_PROFILES: Map<&str, FarmLayout> = Map::new("farm_profiles");
+
+/// Folds the watering heights of plants stored before `Plant::watered` existed.
+pub fn migrate_watered_plants(storage: &mut dyn Storage) -> StdResult<()> {
+    let plots: Vec<((String, u8, u8), Slot)> = FARM_PLOTS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    for ((owner, x, y), mut slot) in plots {
+        let compacted = match slot.plant.as_mut() {
+            Some(plant) => plant.compact_watering(),
+            None => false,
+        };
+
+        if compacted {
+            FARM_PLOTS.save(storage, (owner.as_str(), x, y), &slot)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn migrate_farm_profiles(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy_farms: Vec<(String, FarmLayout)> = LEGACY_FARM_PROFILES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<_>>()?;
+
+    let clock = CLOCK_MODE.may_load(storage)?.unwrap_or_default();
+    for (owner, layout) in legacy_farms {
+        LEGACY_FARM_PROFILES.remove(storage, &owner);
+        FarmProfile::from_layout(layout, clock.clone()).save(storage, &owner)?;
+    }
+
+    Ok(())
+}
 pub const INFORMATION: Item<ContractInformation> = Item::new("info");
+/// Address the admin proposed to hand over to, it becomes admin once it accepts.
+pub const PENDING_ADMIN: Item<String> = Item::new("pending_admin");
+/// Roles granted by the admin, the admin itself holds every role.
+pub const ROLES: Map<&str, Vec<Role>> = Map::new("roles");
 pub const NOIS_PROXY: Item<Addr> = Item::new("nois_proxy");
 pub const NOIS_JOB_LAST_ID: Item<u64> = Item::new("nois_job_last_id");
-pub const NOIS_JOBS: Map<&str, NoiseJob> = Map::new("nois_jobs");
+pub const NOIS_JOBS: Map<&str, PendingNoisJob> = Map::new("pending_nois_jobs");
+pub const NOIS_CONFIG: Item<NoisConfig> = Item::new("nois_config");
+pub const CROP_EVENTS_CONFIG: Item<CropEventsConfig> = Item::new("crop_events_config");
+/// Block of the last crop events roll per farm.
+pub const CROP_EVENT_ROLLS: Map<&str, u64> = Map::new("crop_event_rolls");
+pub const SEASON_LAST_ID: Item<u64> = Item::new("season_last_id");
+pub const SEASONS: Map<u64, Season> = Map::new("seasons");
+/// Final standings of closed seasons, keyed by `(season_id, rank)`.
+pub const SEASON_RESULTS: Map<(u64, u64), LeaderboardEntry> = Map::new("season_results");
+pub const SEED_PRICES: Map<&str, SeedPrice> = Map::new("seed_prices");
+pub const MARKET_CONFIG: Item<MarketConfig> = Item::new("market_config");
+pub const LISTING_LAST_ID: Item<u64> = Item::new("listing_last_id");
+/// CW20 contracts minting the produce of a plant type, the farm has to be their minter.
+pub const CROP_TOKENS: Map<&str, Addr> = Map::new("crop_tokens");
+pub const PRIZE_CONFIG: Item<PrizeConfig> = Item::new("prize_config");
+pub const PRIZE_POOL: Item<Uint128> = Item::new("prize_pool");
+/// Prizes paid out per season.
+pub const PRIZE_PAYOUTS: Map<u64, Vec<PrizePayout>> = Map::new("prize_payouts");
+/// Prizes paid out for the all time leaderboard, they can only be paid once.
+pub const ALL_TIME_PRIZE_PAYOUTS: Item<Vec<PrizePayout>> = Item::new("all_time_prize_payouts");
+pub const EXPANSION_CONFIG: Item<ExpansionConfig> = Item::new("expansion_config");
+pub const PLANT_TYPES: Map<&str, PlantConfig> = Map::new("plant_types");
+/// Nothing is paused while not set.
+pub const PAUSE_STATUS: Item<PauseStatus> = Item::new("pause_status");
+
+/// Default plant types with their growth periods converted to the unit of the clock.
+pub fn default_plant_types(clock: &ClockMode) -> Vec<(PlantType, PlantConfig)> {
+    vec![
+        (
+            "sunflower".parse().unwrap(),
+            PlantConfig {
+                stages: params::STAGES_SUNFLOWER,
+                growth_period: clock.duration(params::GROWTH_PERIOD_SUNFLOWER),
+                watering_tolerance: 0,
+                min_seeds: params::MIN_SEEDS,
+                max_seeds: params::MAX_SEEDS,
+                points: 1,
+                produce: Uint128::new(params::PRODUCE_SUNFLOWER),
+            },
+        ),
+        (
+            "wheat".parse().unwrap(),
+            PlantConfig {
+                stages: params::STAGES_WHEAT,
+                growth_period: clock.duration(params::GROWTH_PERIOD_WHEET),
+                watering_tolerance: 0,
+                min_seeds: params::MIN_SEEDS,
+                max_seeds: params::MAX_SEEDS,
+                points: 1,
+                produce: Uint128::new(params::PRODUCE_WHEAT),
+            },
+        ),
+    ]
+}
+
+pub fn load_plant_config(
+    storage: &dyn Storage,
+    plant_type: &PlantType,
+) -> Result<PlantConfig, ContractError> {
+    PLANT_TYPES
+        .may_load(storage, plant_type.as_str())?
+        .ok_or_else(|| ContractError::UnknownPlant {
+            name: plant_type.to_string(),
+        })
+}
+
+pub fn save_default_plant_types(storage: &mut dyn Storage, clock: &ClockMode) -> StdResult<()> {
+    for (plant_type, config) in default_plant_types(clock) {
+        if !PLANT_TYPES.has(storage, plant_type.as_str()) {
+            PLANT_TYPES.save(storage, plant_type.as_str(), &config)?;
+        }
+    }
+
+    Ok(())
+}
 
 #[cw_serde]
 pub struct Points {
     pub addr: String,
     pub plants: HashMap<String, u64>,
+    /// Points spent on upgrades, they still count towards the leaderboard.
+    #[serde(default)]
+    pub spent: u64,
 }
 
 impl Points {
-    pub fn add(&mut self, plant_type: PlantType) {
+    pub fn new(addr: String) -> Self {
+        Points {
+            addr,
+            plants: HashMap::new(),
+            spent: 0,
+        }
+    }
+
+    pub fn add(&mut self, plant_type: &PlantType, amount: u64) {
         let plant_type_str = plant_type.to_string();
         let plant_points = self.plants.get(&plant_type_str).unwrap_or(&0);
-        self.plants.insert(plant_type_str, plant_points + 1);
+        self.plants.insert(plant_type_str, plant_points + amount);
     }
 
     pub fn total(&self) -> u64 {
@@ -113,6 +416,25 @@ impl Points {
             .reduce(|acc, next| acc + next)
             .unwrap_or(0)
     }
+
+    pub fn available(&self) -> u64 {
+        self.total().saturating_sub(self.spent)
+    }
+
+    pub fn spend(&mut self, amount: u64) -> Result<(), ContractError> {
+        let available = self.available();
+        if available < amount {
+            return Err(ContractError::NotEnoughPoints {
+                received: available.into(),
+                required: amount.into(),
+                max_amount: None,
+            });
+        }
+
+        self.spent += amount;
+
+        Ok(())
+    }
 }
 
 pub struct PointsIndexes<'a> {
@@ -137,6 +459,155 @@ pub fn points<'a>() -> IndexedMap<'a, &'a str, Points, PointsIndexes<'a>> {
     )
 }
 
+/// Points of a single crop, indexed for the per crop leaderboards.
+#[cw_serde]
+pub struct CropPoints {
+    pub addr: String,
+    pub plant_type: String,
+    pub amount: u64,
+}
+
+pub struct CropPointsIndexes<'a> {
+    pub amount: MultiIndex<'a, (String, u64), CropPoints, (&'a str, &'a str)>,
+}
+
+impl<'a> IndexList<CropPoints> for CropPointsIndexes<'a> {
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn cw_storage_plus::Index<CropPoints>> + '_> {
+        let v: Vec<&dyn Index<CropPoints>> = vec![&self.amount];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Keyed by `(plant_type, addr)`.
+pub fn crop_points<'a>() -> IndexedMap<'a, (&'a str, &'a str), CropPoints, CropPointsIndexes<'a>> {
+    IndexedMap::new(
+        "crop_points",
+        CropPointsIndexes {
+            amount: MultiIndex::new(
+                |_k, c| (c.plant_type.clone(), c.amount),
+                "crop_points",
+                "crop_points_amount",
+            ),
+        },
+    )
+}
+
+/// Copies the crop count from the points into the per crop leaderboard.
+pub fn sync_crop_points(
+    storage: &mut dyn Storage,
+    pts: &Points,
+    plant_type: &str,
+) -> StdResult<()> {
+    let amount = pts.plants.get(plant_type).copied().unwrap_or(0);
+
+    crop_points().save(
+        storage,
+        (plant_type, pts.addr.as_str()),
+        &CropPoints {
+            addr: pts.addr.clone(),
+            plant_type: plant_type.to_string(),
+            amount,
+        },
+    )
+}
+
+/// Fills the per crop leaderboard from the points saved before it existed.
+pub fn migrate_crop_points(storage: &mut dyn Storage) -> StdResult<()> {
+    let all_points = points()
+        .range(storage, None, None, Order::Ascending)
+        .map(|res| res.map(|(_, pts)| pts))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for pts in all_points {
+        for plant_type in pts.plants.keys() {
+            sync_crop_points(storage, &pts, plant_type)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cw_serde]
+pub struct SeasonPoints {
+    pub season_id: u64,
+    pub addr: String,
+    pub total: u64,
+}
+
+pub struct SeasonPointsIndexes<'a> {
+    pub total: MultiIndex<'a, (u64, u64), SeasonPoints, (u64, &'a str)>,
+}
+
+impl<'a> IndexList<SeasonPoints> for SeasonPointsIndexes<'a> {
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn cw_storage_plus::Index<SeasonPoints>> + '_> {
+        let v: Vec<&dyn Index<SeasonPoints>> = vec![&self.total];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Keyed by `(season_id, addr)`.
+pub fn season_points<'a>() -> IndexedMap<'a, (u64, &'a str), SeasonPoints, SeasonPointsIndexes<'a>>
+{
+    IndexedMap::new(
+        "season_points",
+        SeasonPointsIndexes {
+            total: MultiIndex::new(
+                |_k, p| (p.season_id, p.total),
+                "season_points",
+                "season_points_total",
+            ),
+        },
+    )
+}
+
+#[cw_serde]
+pub enum ListedItem {
+    SeedNft(SeedNft),
+    CropTokens { token: Addr, amount: Uint128 },
+}
+
+#[cw_serde]
+pub struct Listing {
+    pub id: u64,
+    pub seller: String,
+    pub plant_type: PlantType,
+    pub item: ListedItem,
+    pub price: Coin,
+    pub created_at: u64,
+}
+
+pub struct ListingIndexes<'a> {
+    pub seller: MultiIndex<'a, String, Listing, u64>,
+    pub plant_type: MultiIndex<'a, String, Listing, u64>,
+}
+
+impl<'a> IndexList<Listing> for ListingIndexes<'a> {
+    fn get_indexes(
+        &'_ self,
+    ) -> Box<dyn Iterator<Item = &'_ dyn cw_storage_plus::Index<Listing>> + '_> {
+        let v: Vec<&dyn Index<Listing>> = vec![&self.seller, &self.plant_type];
+        Box::new(v.into_iter())
+    }
+}
+
+pub fn listings<'a>() -> IndexedMap<'a, u64, Listing, ListingIndexes<'a>> {
+    IndexedMap::new(
+        "listings",
+        ListingIndexes {
+            seller: MultiIndex::new(|_k, l| l.seller.clone(), "listings", "listings_seller"),
+            plant_type: MultiIndex::new(
+                |_k, l| l.plant_type.to_string(),
+                "listings",
+                "listings_plant_type",
+            ),
+        },
+    )
+}
+
 fn create_meadow_plot(block: u64) -> Slot {
     Slot {
         r#type: SlotType::Meadow,
@@ -145,6 +616,21 @@ fn create_meadow_plot(block: u64) -> Slot {
     }
 }
 
+/// Plots around a sprinkler, loading them also loads every sprinkler irrigating them.
+pub fn sprinkler_area(x: u8, y: u8) -> Vec<(u8, u8)> {
+    (x.saturating_sub(1)..=x.saturating_add(1))
+        .flat_map(|nx| (y.saturating_sub(1)..=y.saturating_add(1)).map(move |ny| (nx, ny)))
+        .collect()
+}
+
+fn create_sprinkler_plot(block: u64) -> Slot {
+    Slot {
+        r#type: SlotType::Sprinkler,
+        plant: None,
+        created_at: block,
+    }
+}
+
 fn create_field_plot(block: u64) -> Slot {
     Slot {
         r#type: SlotType::Field,
@@ -153,29 +639,31 @@ fn create_field_plot(block: u64) -> Slot {
     }
 }
 
-fn create_plant(plant_type: &PlantType, komple: Option<KomplePlant>, block: u64) -> Plant {
-    match plant_type {
-        PlantType::Sunflower => Plant {
-            r#type: PlantType::Sunflower,
-            stages: 5,
-            komple,
-            growth_period: params::GROWTH_PERIOD_SUNFLOWER,
-            created_at: block,
-            watered_at: vec![block],
-        },
-        PlantType::Wheat => Plant {
-            r#type: PlantType::Wheat,
-            stages: 4,
-            komple,
-            growth_period: params::GROWTH_PERIOD_WHEET,
-            created_at: block,
-            watered_at: vec![block],
-        },
+fn create_plant(
+    plant_type: &PlantType,
+    config: &PlantConfig,
+    komple: Option<KomplePlant>,
+    seed_nft: Option<SeedNft>,
+    block: u64,
+) -> Plant {
+    Plant {
+        r#type: plant_type.clone(),
+        stages: config.stages,
+        komple,
+        growth_period: config.growth_period,
+        created_at: block,
+        watered: 1,
+        last_watered_at: block,
+        watered_at: vec![],
+        watering_tolerance: config.watering_tolerance,
+        seed_nft,
+        effects: vec![],
+        fertilizers: vec![],
     }
 }
 
 impl FarmProfile {
-    pub fn new(block: u64) -> Self {
+    pub fn new(block: u64, clock: ClockMode) -> Self {
         let initial_plots = 9;
 
         let mut plots = vec![];
@@ -187,162 +675,432 @@ impl FarmProfile {
             plots.push(row);
         }
 
-        FarmProfile { plots }
+        FarmProfile::from_layout(FarmLayout { plots }, clock)
+    }
+
+    /// Farm with every plot of the layout, all of them are written on save.
+    pub fn from_layout(layout: FarmLayout, clock: ClockMode) -> Self {
+        let size = layout.plots.len();
+        let mut plots = BTreeMap::new();
+        for (x, row) in layout.plots.into_iter().enumerate() {
+            for (y, mut slot) in row.into_iter().enumerate() {
+                if let Some(plant) = slot.plant.as_mut() {
+                    plant.compact_watering();
+                }
+                plots.insert((x, y), slot);
+            }
+        }
+
+        FarmProfile {
+            size,
+            clock,
+            changed: plots.keys().cloned().collect(),
+            plots,
+        }
+    }
+
+    /// Loads the given plots and their neighbours, which are needed to know if a plot is irrigated.
+    pub fn load(storage: &dyn Storage, owner: &str, area: &[(u8, u8)]) -> StdResult<Option<Self>> {
+        let size: usize = match FARMS.may_load(storage, owner)? {
+            None => return Ok(None),
+            Some(info) => info.size.into(),
+        };
+
+        let mut plots = BTreeMap::new();
+        for (x, y) in area.iter().map(|(x, y)| (usize::from(*x), usize::from(*y))) {
+            if x >= size || y >= size {
+                continue;
+            }
+
+            for nx in x.saturating_sub(1)..=(x + 1).min(size - 1) {
+                for ny in y.saturating_sub(1)..=(y + 1).min(size - 1) {
+                    if let Entry::Vacant(entry) = plots.entry((nx, ny)) {
+                        entry.insert(FARM_PLOTS.load(storage, (owner, nx as u8, ny as u8))?);
+                    }
+                }
+            }
+        }
+
+        Ok(Some(FarmProfile {
+            size,
+            clock: CLOCK_MODE.may_load(storage)?.unwrap_or_default(),
+            plots,
+            changed: BTreeSet::new(),
+        }))
+    }
+
+    pub fn load_all(storage: &dyn Storage, owner: &str) -> StdResult<Option<Self>> {
+        let size: usize = match FARMS.may_load(storage, owner)? {
+            None => return Ok(None),
+            Some(info) => info.size.into(),
+        };
+
+        let plots = FARM_PLOTS
+            .sub_prefix(owner)
+            .range(storage, None, None, Order::Ascending)
+            .map(|item| item.map(|((x, y), slot)| ((usize::from(x), usize::from(y)), slot)))
+            .collect::<StdResult<_>>()?;
+
+        Ok(Some(FarmProfile {
+            size,
+            clock: CLOCK_MODE.may_load(storage)?.unwrap_or_default(),
+            plots,
+            changed: BTreeSet::new(),
+        }))
+    }
+
+    pub fn save(&self, storage: &mut dyn Storage, owner: &str) -> StdResult<()> {
+        FARMS.save(
+            storage,
+            owner,
+            &FarmInfo {
+                size: self.size as u8,
+            },
+        )?;
+
+        for (x, y) in &self.changed {
+            FARM_PLOTS.save(storage, (owner, *x as u8, *y as u8), &self.plots[&(*x, *y)])?;
+        }
+
+        Ok(())
+    }
+
+    pub fn remove(storage: &mut dyn Storage, owner: &str) -> StdResult<()> {
+        let keys: Vec<(u8, u8)> = FARM_PLOTS
+            .sub_prefix(owner)
+            .keys(storage, None, None, Order::Ascending)
+            .collect::<StdResult<_>>()?;
+
+        for (x, y) in keys {
+            FARM_PLOTS.remove(storage, (owner, x, y));
+        }
+        FARMS.remove(storage, owner);
+
+        Ok(())
     }
 
     pub fn get_size(&self) -> usize {
-        self.plots.len()
+        self.size
+    }
+
+    /// Current time in the unit of the farm timers.
+    pub fn now(&self, block: &BlockInfo) -> u64 {
+        self.clock.now(block)
     }
 
     pub fn get_plots(&self) -> String {
         let mut output = String::new();
-        for row in &self.plots {
+        for x in 0..self.size {
+            let row: Vec<&Slot> = (0..self.size)
+                .filter_map(|y| self.plots.get(&(x, y)))
+                .collect();
             output.push_str(&format!("\n {:?}", row));
         }
         output
     }
 
-    pub fn get_plot(&self, x: usize, y: usize) -> Slot {
-        if x > self.get_size() || y > self.get_size() {
-            // throw error
+    pub fn get_plot(&self, x: usize, y: usize) -> Result<Slot, ContractError> {
+        if x >= self.size || y >= self.size {
+            return Err(ContractError::PlotOutOfBounds {
+                x,
+                y,
+                size: self.size,
+            });
         }
 
-        let row = self.plots.get(x);
-        let col = row.unwrap().get(y);
+        self.plots.get(&(x, y)).cloned().ok_or_else(|| {
+            StdError::generic_err(format!("Plot [{}, {}] is not loaded", x, y)).into()
+        })
+    }
+
+    /// Block since which the plot is watered by a neighbouring sprinkler.
+    pub fn irrigated_since(&self, x: usize, y: usize) -> Option<u64> {
+        let size = self.get_size();
+        let mut since: Option<u64> = None;
 
-        col.unwrap().clone()
+        for nx in x.saturating_sub(1)..=(x + 1).min(size - 1) {
+            for ny in y.saturating_sub(1)..=(y + 1).min(size - 1) {
+                let slot = match self.plots.get(&(nx, ny)) {
+                    Some(slot) => slot,
+                    None => continue,
+                };
+                if (nx, ny) != (x, y) && slot.r#type == SlotType::Sprinkler {
+                    since = Some(since.map_or(slot.created_at, |s| s.min(slot.created_at)));
+                }
+            }
+        }
+
+        since
     }
 
-    pub fn set_plot(&mut self, x: usize, y: usize, value: Slot) {
-        self.plots[x][y] = value;
+    pub fn set_plot(&mut self, x: usize, y: usize, value: Slot) -> Result<(), ContractError> {
+        if x >= self.size || y >= self.size {
+            return Err(ContractError::PlotOutOfBounds {
+                x,
+                y,
+                size: self.size,
+            });
+        }
+
+        self.plots.insert((x, y), value);
+        self.changed.insert((x, y));
+
+        Ok(())
     }
 
     pub fn upgrade_size(&mut self, amount: usize, block: u64) {
-        for row in &mut self.plots {
-            for _ in 0..amount {
-                row.push(create_meadow_plot(block));
+        let size = self.size + amount;
+        for x in 0..size {
+            for y in 0..size {
+                if x >= self.size || y >= self.size {
+                    self.plots.insert((x, y), create_meadow_plot(block));
+                    self.changed.insert((x, y));
+                }
             }
         }
 
-        let mut new_row = vec![];
-        for _ in 0..self.get_size() + amount {
-            new_row.push(create_meadow_plot(block));
+        self.size = size;
+    }
+
+    /// Seed NFTs held for plants on the loaded plots, with their plot coordinates.
+    pub fn seed_nfts(&self) -> Vec<(usize, usize, SeedNft)> {
+        self.plots
+            .iter()
+            .filter_map(|((x, y), slot)| {
+                let nft = slot.plant.as_ref().and_then(|p| p.seed_nft.clone())?;
+                Some((*x, *y, nft))
+            })
+            .collect()
+    }
+
+    /// Rolls crop events for every growing plant, returns the applied events.
+    pub fn roll_crop_events(
+        &mut self,
+        randomness: [u8; 32],
+        config: &CropEventsConfig,
+        block: u64,
+    ) -> Vec<(usize, usize, CropEvent)> {
+        let chances = [
+            (CropEvent::Drought, config.drought),
+            (CropEvent::Pests, config.pests),
+            (CropEvent::BumperCrop, config.bumper_crop),
+            (CropEvent::GoldenMutation, config.golden_mutation),
+        ];
+
+        let mut applied = vec![];
+        let size = self.get_size();
+        for x in 0..size {
+            for y in 0..size {
+                let irrigated_since = self.irrigated_since(x, y);
+                let plant = match self.plots.get_mut(&(x, y)).and_then(|s| s.plant.as_mut()) {
+                    Some(plant) if plant.is_growing(block, irrigated_since) => plant,
+                    _ => continue,
+                };
+
+                let mut provider = sub_randomness_with_key(randomness, format!("{}/{}", x, y));
+                for (event, chance) in chances.iter() {
+                    let roll: u8 = int_in_range(provider.provide(), 1, 100);
+                    if roll <= *chance && plant.apply_event(event.clone(), block) {
+                        applied.push((x, y, event.clone()));
+                        self.changed.insert((x, y));
+                    }
+                }
+            }
         }
 
-        for _ in 0..amount {
-            self.plots.push(new_row.clone());
+        applied
+    }
+
+    /// Tills the plot and returns the dead plant it replaced, if any.
+    pub fn till(&mut self, x: usize, y: usize, block: u64) -> Result<Option<Plant>, ContractError> {
+        let plot = self.get_plot(x, y)?;
+        if !plot.can_till(block, &self.clock, self.irrigated_since(x, y)) {
+            return Err(ContractError::PlotNotTillable { x, y });
         }
 
-        println!(
-            "\nUpgrading farm size to {}x{}",
-            self.get_size(),
-            self.get_size()
-        );
+        self.set_plot(x, y, create_field_plot(block))?;
+
+        Ok(plot.plant)
     }
 
-    pub fn till(&mut self, x: usize, y: usize, block: u64) -> Result<(), ContractError> {
-        let plot = self.get_plot(x, y);
-        if !plot.can_till(block) {
-            return Err(throw_err(&format!(
-                "Plot [{}, {}] must be meadow or field with dead plant to till",
-                x, y
-            )));
+    pub fn build_sprinkler(&mut self, x: usize, y: usize, block: u64) -> Result<(), ContractError> {
+        let plot = self.get_plot(x, y)?;
+        if plot.get_real_type(block, &self.clock) != SlotType::Meadow {
+            return Err(ContractError::PlotNotMeadow { x, y });
         }
 
-        self.set_plot(x, y, create_field_plot(block));
+        self.settle_irrigation(x, y, block);
+        self.set_plot(x, y, create_sprinkler_plot(block))
+    }
 
-        Ok(())
+    pub fn remove_sprinkler(
+        &mut self,
+        x: usize,
+        y: usize,
+        block: u64,
+    ) -> Result<(), ContractError> {
+        let plot = self.get_plot(x, y)?;
+        if plot.r#type != SlotType::Sprinkler {
+            return Err(ContractError::NoSprinkler { x, y });
+        }
+
+        self.settle_irrigation(x, y, block);
+        self.set_plot(x, y, create_meadow_plot(block))
     }
 
+    /// Stores the stages the plants around a sprinkler were irrigated so far,
+    /// irrigation is only derived from the current sprinklers and changing one would rewrite it.
+    fn settle_irrigation(&mut self, x: usize, y: usize, block: u64) {
+        let size = self.get_size();
+        for nx in x.saturating_sub(1)..=(x + 1).min(size - 1) {
+            for ny in y.saturating_sub(1)..=(y + 1).min(size - 1) {
+                let irrigated_since = self.irrigated_since(nx, ny);
+                let plant = match self.plots.get_mut(&(nx, ny)).and_then(|s| s.plant.as_mut()) {
+                    Some(plant) if (nx, ny) != (x, y) => plant,
+                    _ => continue,
+                };
+
+                let watered = plant.watered_stages(block, irrigated_since);
+                if watered != plant.watered {
+                    plant.watered = watered;
+                    self.changed.insert((nx, ny));
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn plant_seed(
         &mut self,
         x: usize,
         y: usize,
         plant_type: &PlantType,
+        config: &PlantConfig,
         komple: Option<KomplePlant>,
+        seed_nft: Option<SeedNft>,
         block: u64,
-    ) {
-        let plot = self.get_plot(x, y);
-        if plot.get_real_type(block) == SlotType::Field && plot.plant.is_none() {
-            self.set_plot(
-                x,
-                y,
-                Slot {
-                    plant: Some(create_plant(plant_type, komple, block)),
-                    ..plot
-                },
-            );
+    ) -> Result<(), ContractError> {
+        let plot = self.get_plot(x, y)?;
+        if plot.get_real_type(block, &self.clock) != SlotType::Field {
+            return Err(ContractError::PlotNotField { x, y });
+        }
+
+        if plot.plant.is_some() {
+            return Err(ContractError::PlotOccupied { x, y });
         }
+
+        self.set_plot(
+            x,
+            y,
+            Slot {
+                plant: Some(create_plant(plant_type, config, komple, seed_nft, block)),
+                ..plot
+            },
+        )
     }
 
     pub fn water_plant(&mut self, x: usize, y: usize, block: u64) -> Result<(), ContractError> {
-        let plot = self.get_plot(x, y);
-        let updated_plant = match plot.plant {
-            None => Err(throw_err(&format!(
-                "Plot [{}, {}] must contain a plant to water.",
-                x, y
-            ))),
-            Some(plant) => {
-                if !plant.can_water(block) {
-                    if plant.can_harvest(block) {
-                        return Err(throw_err(&format!(
-                            "Plant [{}, {}] is fully grown and cannot be watered anymore.",
-                            x, y
-                        )));
-                    }
+        let plot = self.get_plot(x, y)?;
+        let mut plant = plot.plant.clone().ok_or(ContractError::NoPlant { x, y })?;
+        let irrigated_since = self.irrigated_since(x, y);
 
-                    if plant.is_dead(block) {
-                        return Err(throw_err(&format!(
-                            "Plant [{}, {}] is dead and cannot be watered anymore.",
-                            x, y
-                        )));
-                    }
+        if !plant.can_water(block, irrigated_since) {
+            if plant.is_dead(block, irrigated_since) {
+                return Err(ContractError::PlantDead { x, y });
+            }
 
-                    return Err(throw_err(&format!(
-                        "Plant [{}, {}] cannot be watered.",
-                        x, y
-                    )));
-                }
+            let watered_stages = plant.watered_stages(block, irrigated_since);
+            let reason = if watered_stages >= plant.stages {
+                WaterBlockReason::FullyGrown
+            } else {
+                WaterBlockReason::AlreadyWatered
+            };
 
-                let mut p2 = plant.clone();
-                p2.watered_at.push(block);
+            return Err(ContractError::PlantNotWaterable { x, y, reason });
+        }
 
-                Ok(p2)
-            }
-        }?;
+        plant.water(block);
 
         self.set_plot(
             x,
             y,
             Slot {
-                plant: Some(updated_plant),
+                plant: Some(plant),
                 ..plot
             },
-        );
+        )
+    }
 
-        Ok(())
+    pub fn fertilize(
+        &mut self,
+        x: usize,
+        y: usize,
+        fertilizer: Fertilizer,
+        block: u64,
+    ) -> Result<(), ContractError> {
+        let plot = self.get_plot(x, y)?;
+        let mut plant = plot.plant.clone().ok_or(ContractError::NoPlant { x, y })?;
+        let irrigated_since = self.irrigated_since(x, y);
+
+        if plant.is_dead(block, irrigated_since) {
+            return Err(ContractError::PlantDead { x, y });
+        }
+
+        let watered_stages = plant.watered_stages(block, irrigated_since);
+        let current_stage = plant.get_current_stage(block);
+        let reason = if plant.has_fertilizer(&fertilizer) {
+            Some(FertilizeBlockReason::AlreadyFertilized)
+        } else if !plant.is_growing(block, irrigated_since) || current_stage >= plant.stages {
+            Some(FertilizeBlockReason::FullyGrown)
+        } else if fertilizer == Fertilizer::Growth && watered_stages < current_stage {
+            // Skipping an unwatered stage would kill the plant.
+            Some(FertilizeBlockReason::NeedsWater)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            return Err(ContractError::PlantNotFertilizable { x, y, reason });
+        }
+
+        plant.fertilizers.push(fertilizer);
+
+        self.set_plot(
+            x,
+            y,
+            Slot {
+                plant: Some(plant),
+                ..plot
+            },
+        )
     }
 
-    pub fn harvest(&mut self, x: usize, y: usize, block: u64) -> Result<PlantType, ContractError> {
-        let plot = self.get_plot(x, y);
-
-        match plot.plant {
-            None => Err(throw_err(&format!(
-                "Plot [{}, {}] must contain a plant to harvest.",
-                x, y
-            ))),
-            Some(plant) => {
-                if !plant.can_harvest(block) {
-                    return Err(throw_err(&format!(
-                        "Plant [{}, {}] must be fully grown and watered to harvest it.",
-                        x, y
-                    )));
-                }
+    /// Returns the plant if it can be harvested, without changing the farm.
+    pub fn harvestable_plant(
+        &self,
+        x: usize,
+        y: usize,
+        block: u64,
+    ) -> Result<Plant, ContractError> {
+        let plot = self.get_plot(x, y)?;
+        let plant = plot.plant.ok_or(ContractError::NoPlant { x, y })?;
 
-                self.set_plot(x, y, create_field_plot(block));
+        let irrigated_since = self.irrigated_since(x, y);
+        if plant.is_dead(block, irrigated_since) {
+            return Err(ContractError::PlantDead { x, y });
+        }
 
-                Ok(plant.r#type)
-            }
+        if !plant.can_harvest(block, irrigated_since) {
+            return Err(ContractError::NotHarvestable { x, y });
         }
+
+        Ok(plant)
+    }
+
+    pub fn harvest(&mut self, x: usize, y: usize, block: u64) -> Result<PlantType, ContractError> {
+        let plant = self.harvestable_plant(x, y, block)?;
+        self.set_plot(x, y, create_field_plot(block))?;
+
+        Ok(plant.r#type)
     }
 }
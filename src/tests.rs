@@ -1,16 +1,42 @@
-use crate::contract::{execute, instantiate};
+use crate::contract::{execute, instantiate, migrate, query};
 
-use crate::msg::{ContractInformation, ExecuteMsg, InstantiateMsg};
-use crate::state::INFORMATION;
+use crate::error::{FertilizeBlockReason, WaterBlockReason};
+use crate::farm::{
+    ClockMode, CropEvent, Fertilizer, KomplePlant, Plant, PlantConfig, SeedNft, Slot, SlotType,
+};
+use crate::helpers::transfer_nft_msg;
+use crate::msg::{
+    ContractInformation, CropEventsConfig, CropTokenResponse, Cw20HookMsg, Cw721HookMsg,
+    ExecuteMsg, ExpansionConfig, FarmAction, FarmActionResult, FarmNft, InstantiateMsg,
+    LeaderboardEntry, MarketConfig, MigrateMsg, NoisConfig, PausableAction, PauseStatus,
+    PaymentMethod, PendingNoisJobResponse, PlantTypeResponse, PrizeConfig, PrizePayout, QueryMsg,
+    Role, Season, SeedPrice, SeedPriceResponse,
+};
+use crate::params;
+use crate::state::{
+    points, season_points, FarmInfo, FarmLayout, FarmProfile, FarmProfileDto, Listing,
+    PendingNoisJob, Points, SeasonPoints, FARMS, FARM_PLOTS, INFORMATION,
+};
+use crate::ContractError;
 
 use cosmwasm_std::testing::{
     mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
 };
 use cosmwasm_std::{
-    to_binary, Addr, DepsMut, Env, OwnedDeps, QuerierResult, SystemError, SystemResult, WasmQuery,
+    coin, from_binary, from_slice, to_binary, Addr, BankMsg, Coin, CosmosMsg, Deps, DepsMut, Env,
+    HexBinary, OwnedDeps, QuerierResult, Response, StdError, SubMsg, SystemError, SystemResult,
+    Timestamp, Uint128, WasmMsg, WasmQuery,
 };
+use cw2::set_contract_version;
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw721::{Cw721ExecuteMsg, Cw721ReceiveMsg};
+use cw_storage_plus::Map;
 use komple_framework_metadata_module::msg::MetadataResponse;
-use komple_framework_metadata_module::state::{MetaInfo, Metadata};
+use komple_framework_metadata_module::state::{MetaInfo, Metadata, Trait};
+use komple_framework_mint_module::msg::CollectionsResponse;
+use komple_framework_types::{modules::token::SubModules, shared::query::ResponseWrapper};
+use nois::NoisCallback;
+use proptest::prelude::*;
 
 pub struct KompleAddrs {
     pub metadata: Addr,
@@ -41,6 +67,12 @@ pub fn setup_test(
     (dependencies, env)
 }
 
+/// Migrates a contract which still stores its data in the legacy layout.
+pub fn migrate_legacy(deps: DepsMut, env: Env) {
+    set_contract_version(deps.storage, "crates.io:farm_template", "0.1.0").unwrap();
+    migrate(deps, env, MigrateMsg {}).unwrap();
+}
+
 pub fn init_farm(addr: &str, deps: DepsMut) {
     let msg = ExecuteMsg::Start {};
     let info = mock_info(addr, &[]);
@@ -55,6 +87,147 @@ pub fn till(addr: &str, x: u8, y: u8, deps: DepsMut) {
     let _res = execute(deps, mock_env(), info, msg).unwrap();
 }
 
+pub fn send_seed(
+    addr: &str,
+    collection_addr: &str,
+    x: u8,
+    y: u8,
+    env: Env,
+    deps: DepsMut,
+) -> Result<Response, ContractError> {
+    send_seed_token(addr, collection_addr, "1", x, y, env, deps)
+}
+
+pub fn send_seed_token(
+    addr: &str,
+    collection_addr: &str,
+    token_id: &str,
+    x: u8,
+    y: u8,
+    env: Env,
+    deps: DepsMut,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: addr.to_string(),
+        token_id: token_id.to_string(),
+        msg: to_binary(&Cw721HookMsg::Seed { x, y }).unwrap(),
+    });
+
+    execute(deps, env, mock_info(collection_addr, &[]), msg)
+}
+
+/// Waters a freshly seeded sunflower every stage and returns the env at which it can be harvested.
+pub fn grow_sunflower(addr: &str, x: u8, y: u8, mut env: Env, deps: &mut DepsMut) -> Env {
+    for _ in 0..4 {
+        env.block.height += 10;
+        let msg = ExecuteMsg::WaterPlant { x, y };
+        execute(deps.branch(), env.clone(), mock_info(addr, &[]), msg).unwrap();
+    }
+
+    env
+}
+
+pub fn get_farm_nfts(addr: &str, deps: Deps) -> Vec<FarmNft> {
+    let msg = QueryMsg::FarmNfts {
+        address: addr.to_string(),
+    };
+
+    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+}
+
+/// Decodes a cw721 message sent to `collection_addr`.
+pub fn cw721_msg(msg: &CosmosMsg, collection_addr: &str) -> Cw721ExecuteMsg {
+    match msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr, msg, ..
+        }) if contract_addr == collection_addr => from_binary(msg).unwrap(),
+        _ => panic!("Not a cw721 message: {:?}", msg),
+    }
+}
+
+pub fn get_farm_profile(addr: &str, deps: Deps) -> FarmProfileDto {
+    let msg = QueryMsg::GetFarmProfile {
+        address: addr.to_string(),
+    };
+
+    from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+}
+
+/// Mocks Komple mint, token and metadata modules for a seed of `plant_type`
+/// sent from `collection_addr`.
+pub fn komple_seed_wasm_query(
+    wasm_query: &WasmQuery,
+    collection_addr: &str,
+    plant_type: &str,
+) -> QuerierResult {
+    komple_wasm_query(
+        wasm_query,
+        collection_addr,
+        vec![Trait {
+            trait_type: "type".into(),
+            value: plant_type.into(),
+        }],
+    )
+}
+
+pub fn komple_wasm_query(
+    wasm_query: &WasmQuery,
+    collection_addr: &str,
+    attributes: Vec<Trait>,
+) -> QuerierResult {
+    match wasm_query {
+        WasmQuery::Smart {
+            contract_addr,
+            msg: _msg,
+        } if *contract_addr == collection_addr => SystemResult::Ok(
+            to_binary(&ResponseWrapper::new(
+                "sub_modules",
+                SubModules {
+                    metadata: Some(get_komple_addrs().metadata),
+                    whitelist: None,
+                },
+            ))
+            .into(),
+        ),
+        WasmQuery::Smart {
+            contract_addr,
+            msg: _msg,
+        } if *contract_addr == get_komple_addrs().metadata => SystemResult::Ok(
+            to_binary(&ResponseWrapper::new(
+                "metadata",
+                MetadataResponse {
+                    metadata_id: 1,
+                    metadata: Metadata {
+                        attributes: attributes.clone(),
+                        meta_info: MetaInfo {
+                            image: None,
+                            external_url: None,
+                            description: None,
+                            animation_url: None,
+                            youtube_url: None,
+                        },
+                    },
+                },
+            ))
+            .into(),
+        ),
+        WasmQuery::Smart {
+            contract_addr,
+            msg: _msg,
+        } if *contract_addr == get_komple_addrs().mint => SystemResult::Ok(
+            to_binary(&ResponseWrapper::new(
+                "collections",
+                vec![CollectionsResponse {
+                    address: collection_addr.to_string(),
+                    collection_id: 1,
+                }],
+            ))
+            .into(),
+        ),
+        _ => general_handle_wasm_query(wasm_query),
+    }
+}
+
 pub fn general_handle_wasm_query(wasm_query: &WasmQuery) -> QuerierResult {
     match wasm_query {
         WasmQuery::Smart {
@@ -99,6 +272,7 @@ fn proper_initialization() {
         admin: None,
         komple_mint_addr: None,
         nois_proxy: None,
+        clock_mode: None,
     };
     let info = mock_info("creator", &[]);
 
@@ -118,6 +292,7 @@ fn unauthorized_config_update() {
         admin: Some("admin".to_string()),
         komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
         nois_proxy: None,
+        clock_mode: None,
     }));
 
     let sender = "non-admin";
@@ -126,6 +301,7 @@ fn unauthorized_config_update() {
         contract_information: ContractInformation {
             admin: "non-admin".to_string(),
             komple_mint_addr: Some("new-komple-mint".to_string()),
+            treasury: None,
         },
     };
 
@@ -138,6 +314,7 @@ fn authorized_config_update() {
         admin: Some("admin".to_string()),
         komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
         nois_proxy: None,
+        clock_mode: None,
     }));
 
     let sender = "admin";
@@ -146,6 +323,36 @@ fn authorized_config_update() {
         contract_information: ContractInformation {
             admin: "new-admin".to_string(),
             komple_mint_addr: Some("new-komple-mint".to_string()),
+            treasury: None,
+        },
+    };
+
+    let err = execute(deps.as_mut(), env.to_owned(), auth_info.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidContractInformation {
+            reason: "the admin can only change through ProposeAdmin".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::UpdateContractInformation {
+        contract_information: ContractInformation {
+            admin: "admin".to_string(),
+            komple_mint_addr: Some("new-komple-mint".to_string()),
+            treasury: Some("Treasury".to_string()),
+        },
+    };
+    let err = execute(deps.as_mut(), env.to_owned(), auth_info.clone(), msg).unwrap_err();
+    assert!(matches!(
+        err,
+        ContractError::Std(StdError::GenericErr { .. })
+    ));
+
+    let msg = ExecuteMsg::UpdateContractInformation {
+        contract_information: ContractInformation {
+            admin: "admin".to_string(),
+            komple_mint_addr: Some("new-komple-mint".to_string()),
+            treasury: None,
         },
     };
 
@@ -156,8 +363,2821 @@ fn authorized_config_update() {
     assert_eq!(
         information,
         ContractInformation {
-            admin: "new-admin".to_string(),
-            komple_mint_addr: Some("new-komple-mint".to_string())
+            admin: "admin".to_string(),
+            komple_mint_addr: Some("new-komple-mint".to_string()),
+            treasury: None,
+        }
+    )
+}
+
+#[test]
+fn admin_transfer() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: Some("admin".to_string()),
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("new-admin", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPendingAdmin {});
+
+    let msg = ExecuteMsg::ProposeAdmin {
+        admin: Some("new-admin".to_string()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("new-admin", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), msg).unwrap();
+
+    let pending: Option<String> =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingAdmin {}).unwrap())
+            .unwrap();
+    assert_eq!(pending, Some("new-admin".to_string()));
+
+    // The current admin stays in charge until the proposal is accepted.
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("someone-else", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    assert_eq!(INFORMATION.load(&deps.storage).unwrap().admin, "admin");
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("new-admin", &[]),
+        ExecuteMsg::AcceptAdmin {},
+    )
+    .unwrap();
+    assert_eq!(INFORMATION.load(&deps.storage).unwrap().admin, "new-admin");
+
+    let pending: Option<String> =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingAdmin {}).unwrap())
+            .unwrap();
+    assert_eq!(pending, None);
+
+    let msg = ExecuteMsg::ProposeAdmin { admin: None };
+    let err = execute(deps.as_mut(), env, mock_info("admin", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn roles() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: Some("admin".to_string()),
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let operator = mock_info("operator", &[]);
+    let pause = ExecuteMsg::SetPaused { paused: true };
+    let err = execute(deps.as_mut(), env.clone(), operator.clone(), pause.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let msg = ExecuteMsg::GrantRole {
+        address: "operator".to_string(),
+        role: Role::Operator,
+    };
+    let err = execute(deps.as_mut(), env.clone(), operator.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), msg).unwrap();
+
+    let roles: Vec<Role> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Roles {
+                address: "operator".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(roles, vec![Role::Operator]);
+
+    execute(deps.as_mut(), env.clone(), operator.clone(), pause).unwrap();
+
+    // A role only grants its own permissions.
+    let msg = ExecuteMsg::SetupFarm {
+        farm: FarmLayout {
+            plots: vec![vec![Slot {
+                r#type: SlotType::Meadow,
+                plant: None,
+                created_at: env.block.height,
+            }]],
+        },
+        addr: Addr::unchecked("player"),
+    };
+    let err = execute(deps.as_mut(), env.clone(), operator.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let grant = ExecuteMsg::GrantRole {
+        address: "game-master".to_string(),
+        role: Role::GameMaster,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), grant).unwrap();
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("game-master", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let treasurer = mock_info("treasurer", &[]);
+    let treasury_msgs = [
+        ExecuteMsg::UpdateMarketConfig {
+            config: MarketConfig { fee_bps: 100 },
+        },
+        ExecuteMsg::UpdateExpansionConfig {
+            config: ExpansionConfig {
+                step: 1,
+                max_size: 12,
+                price: Some(coin(100, "ujuno")),
+                points_price: None,
+            },
+        },
+        ExecuteMsg::SetCropToken {
+            plant_type: "wheat".to_string(),
+            token: Some("wheat_token".to_string()),
+        },
+    ];
+    for msg in treasury_msgs.clone() {
+        let err = execute(deps.as_mut(), env.clone(), treasurer.clone(), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+    let grant = ExecuteMsg::GrantRole {
+        address: "treasurer".to_string(),
+        role: Role::Treasurer,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), grant).unwrap();
+    for msg in treasury_msgs {
+        execute(deps.as_mut(), env.clone(), treasurer.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::RevokeRole {
+        address: "operator".to_string(),
+        role: Role::Operator,
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), msg).unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env,
+        operator,
+        ExecuteMsg::SetPaused { paused: false },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+}
+
+#[test]
+fn expand_farm_with_points() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+
+    let mut pts = Points::new(player.to_string());
+    for _ in 0..12 {
+        pts.add(&"sunflower".parse().unwrap(), 1);
+    }
+    points().save(&mut deps.storage, player, &pts).unwrap();
+
+    let msg = ExecuteMsg::ExpandFarm {
+        payment: PaymentMethod::Points,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(player, &[coin(100, "ujunox")]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFunds { required: None });
+
+    let res = execute(deps.as_mut(), env, mock_info(player, &[]), msg).unwrap();
+
+    assert_eq!(res.attributes[1].value, "10x10");
+    assert_eq!(get_farm_profile(player, deps.as_ref()).size, 10);
+
+    let pts = points().load(&deps.storage, player).unwrap();
+    assert_eq!(pts.total(), 12);
+    assert_eq!(pts.available(), 2);
+}
+
+#[test]
+#[should_panic(expected = "NotEnoughPoints")]
+fn expand_farm_without_points() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+
+    let msg = ExecuteMsg::ExpandFarm {
+        payment: PaymentMethod::Points,
+    };
+    execute(deps.as_mut(), env, mock_info(player, &[]), msg).unwrap();
+}
+
+#[test]
+fn expand_farm_with_funds() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let msg = ExecuteMsg::UpdateExpansionConfig {
+        config: ExpansionConfig {
+            step: 2,
+            max_size: 12,
+            price: Some(coin(100, "ujunox")),
+            points_price: None,
+        },
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+
+    let msg = ExecuteMsg::ExpandFarm {
+        payment: PaymentMethod::Funds,
+    };
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(player, &[coin(50, "ujunox")]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(player, &[]),
+        ExecuteMsg::ExpandFarm {
+            payment: PaymentMethod::Points,
+        },
+    );
+    assert!(res.is_err());
+
+    let info = mock_info(player, &[coin(100, "ujunox")]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(get_farm_profile(player, deps.as_ref()).size, 11);
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(BankMsg::Send {
+            to_address: "creator".to_string(),
+            amount: vec![coin(100, "ujunox")],
+        })]
+    );
+
+    // Last expansion is capped by the max size.
+    execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+    assert_eq!(get_farm_profile(player, deps.as_ref()).size, 12);
+
+    let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+    assert_eq!(err, ContractError::FarmMaxSize { max_size: 12 });
+}
+
+#[test]
+fn plant_registry() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: Some("admin".to_string()),
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let config = PlantConfig {
+        stages: 3,
+        growth_period: 20,
+        watering_tolerance: 1,
+        min_seeds: 1,
+        max_seeds: 3,
+        points: 2,
+        produce: Uint128::new(5),
+    };
+    let msg = ExecuteMsg::SetPlantType {
+        name: "Carrot".to_string(),
+        config: config.clone(),
+    };
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("non-admin", &[]),
+        msg.clone(),
+    );
+    assert!(res.is_err());
+
+    execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), msg).unwrap();
+
+    let invalid = ExecuteMsg::SetPlantType {
+        name: "potato".to_string(),
+        config: PlantConfig {
+            growth_period: 0,
+            ..config.clone()
+        },
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), invalid).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPlantConfig {
+            reason: "growth_period must be greater than zero".to_string()
+        }
+    );
+
+    let invalid = ExecuteMsg::SetPlantType {
+        name: "potato".to_string(),
+        config: PlantConfig {
+            stages: u64::MAX,
+            ..config.clone()
+        },
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("admin", &[]), invalid).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPlantConfig {
+            reason: "stages must be between 1 and 100".to_string()
+        }
+    );
+
+    let res: Vec<PlantTypeResponse> = from_binary(
+        &query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::PlantTypes {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let names: Vec<&str> = res.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["carrot", "sunflower", "wheat"]);
+
+    let res: PlantConfig = from_binary(
+        &query(
+            deps.as_ref(),
+            env,
+            QueryMsg::PlantType {
+                name: " Carrot ".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res, config);
+}
+
+#[test]
+fn out_of_bounds_plots() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+
+    let out_of_bounds = |x: usize, y: usize| ContractError::PlotOutOfBounds { x, y, size: 9 };
+
+    let msgs = vec![
+        ExecuteMsg::TillGround { x: 200, y: 0 },
+        ExecuteMsg::WaterPlant { x: 0, y: 9 },
+        ExecuteMsg::Harvest { x: 9, y: 9 },
+    ];
+    let expected = vec![
+        out_of_bounds(200, 0),
+        out_of_bounds(0, 9),
+        out_of_bounds(9, 9),
+    ];
+
+    for (msg, expected) in msgs.into_iter().zip(expected) {
+        let err = execute(deps.as_mut(), env.clone(), mock_info(player, &[]), msg).unwrap_err();
+        assert_eq!(err, expected);
+    }
+
+    let msg = ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+        sender: player.to_string(),
+        token_id: "1".to_string(),
+        msg: to_binary(&Cw721HookMsg::Seed { x: 3, y: 12 }).unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(collection_addr, &[]),
+        msg,
+    )
+    .unwrap_err();
+    assert_eq!(err.to_string(), out_of_bounds(3, 12).to_string());
+
+    let mut farm = FarmProfile::new(env.block.height, ClockMode::BlockHeight);
+    let slot = Slot {
+        r#type: SlotType::Field,
+        plant: None,
+        created_at: env.block.height,
+    };
+    let err = farm.set_plot(9, 0, slot.clone()).unwrap_err();
+    assert_eq!(err, out_of_bounds(9, 0));
+    assert!(farm.set_plot(8, 8, slot).is_ok());
+}
+
+#[test]
+fn typed_farm_errors() {
+    let (mut deps, mut env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    let info = mock_info(player, &[]);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::TillGround { x: 0, y: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoFarm {
+            address: player.to_string()
+        }
+    );
+
+    init_farm(player, deps.as_mut());
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::Start {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::FarmAlreadyExists {
+            address: player.to_string()
+        }
+    );
+
+    let err = send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::PlotNotField { x: 0, y: 0 });
+
+    till(player, 0, 0, deps.as_mut());
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::TillGround { x: 0, y: 0 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PlotNotTillable { x: 0, y: 0 });
+
+    let water = ExecuteMsg::WaterPlant { x: 0, y: 0 };
+    let harvest = ExecuteMsg::Harvest { x: 0, y: 0 };
+
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), water.clone()).unwrap_err();
+    assert_eq!(err, ContractError::NoPlant { x: 0, y: 0 });
+
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+    let err = send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::PlotOccupied { x: 0, y: 0 });
+
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), water.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PlantNotWaterable {
+            x: 0,
+            y: 0,
+            reason: WaterBlockReason::AlreadyWatered
         }
+    );
+
+    env.block.height += 10;
+    execute(deps.as_mut(), env.clone(), info.clone(), water.clone()).unwrap();
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), harvest.clone()).unwrap_err();
+    assert_eq!(err, ContractError::NotHarvestable { x: 0, y: 0 });
+
+    env.block.height += 30;
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), water).unwrap_err();
+    assert_eq!(err, ContractError::PlantDead { x: 0, y: 0 });
+    let err = execute(deps.as_mut(), env, info, harvest).unwrap_err();
+    assert_eq!(err, ContractError::PlantDead { x: 0, y: 0 });
+}
+
+#[test]
+fn seed_nft_custody() {
+    let (mut deps, mut env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    let info = mock_info(player, &[]);
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    till(player, 0, 1, deps.as_mut());
+    till(player, 0, 2, deps.as_mut());
+    send_seed_token(
+        player,
+        collection_addr,
+        "7",
+        0,
+        0,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+    send_seed_token(
+        player,
+        collection_addr,
+        "8",
+        0,
+        1,
+        env.clone(),
+        deps.as_mut(),
     )
+    .unwrap();
+    send_seed_token(
+        player,
+        collection_addr,
+        "9",
+        0,
+        2,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+
+    let nfts = get_farm_nfts(player, deps.as_ref());
+    assert_eq!(nfts.len(), 3);
+    assert_eq!(
+        nfts[0],
+        FarmNft {
+            x: 0,
+            y: 0,
+            nft: SeedNft {
+                collection: collection_addr.to_string(),
+                token_id: "7".to_string(),
+            },
+        }
+    );
+
+    // Only the plant at [0, 0] is watered, the others die.
+    for _ in 0..4 {
+        env.block.height += 10;
+        let msg = ExecuteMsg::WaterPlant { x: 0, y: 0 };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        cw721_msg(&res.messages.last().unwrap().msg, collection_addr),
+        Cw721ExecuteMsg::Burn {
+            token_id: "7".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::TillGround { x: 0, y: 1 };
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        cw721_msg(&res.messages[0].msg, collection_addr),
+        Cw721ExecuteMsg::TransferNft {
+            recipient: player.to_string(),
+            token_id: "8".to_string()
+        }
+    );
+
+    let nfts = get_farm_nfts(player, deps.as_ref());
+    assert_eq!(nfts.len(), 1);
+    assert_eq!(nfts[0].nft.token_id, "9");
+
+    let res = execute(deps.as_mut(), env, info, ExecuteMsg::Stop {}).unwrap();
+    assert_eq!(
+        cw721_msg(&res.messages[0].msg, collection_addr),
+        Cw721ExecuteMsg::TransferNft {
+            recipient: player.to_string(),
+            token_id: "9".to_string()
+        }
+    );
+    assert!(get_farm_nfts(player, deps.as_ref()).is_empty());
+}
+
+#[test]
+fn nois_job_timeout() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: Some("nois_proxy".to_string()),
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+    let mut env = grow_sunflower(player, 0, 0, env, &mut deps.as_mut());
+
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    let info = mock_info(player, &[coin(300, "unois")]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+    // The funds go to the proxy along with the request, the contract doesn't keep them.
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            funds,
+            ..
+        }) => {
+            assert_eq!(contract_addr, "nois_proxy");
+            assert_eq!(funds, &info.funds);
+        }
+        msg => panic!("unexpected message {:?}", msg),
+    }
+
+    let pending_jobs = |deps: Deps| -> Vec<PendingNoisJobResponse> {
+        let msg = QueryMsg::PendingNoisJobs {
+            start_after: None,
+            limit: None,
+        };
+        from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    };
+
+    let jobs = pending_jobs(deps.as_ref());
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].job_id, "1");
+    assert_eq!(jobs[0].job.created_at, env.block.height);
+    assert_eq!(jobs[0].job.funds, info.funds);
+
+    let anyone = mock_info("anyone", &[]);
+    let settle = ExecuteMsg::SettleNoisJob {
+        job_id: "1".to_string(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), anyone.clone(), settle).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoisJobNotExpired {
+            job_id: "1".to_string(),
+            expires_at: env.block.height + 100,
+        }
+    );
+
+    env.block.height += 100;
+    let retry = ExecuteMsg::RetryNoisJob {
+        job_id: "1".to_string(),
+    };
+    let retry_funds = [coin(500, "unois")];
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("anyone", &retry_funds),
+        retry,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2].value, "2");
+
+    let jobs = pending_jobs(deps.as_ref());
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].job_id, "2");
+    assert_eq!(jobs[0].job.funds, retry_funds);
+
+    // Jobs stored before the funds were recorded still load.
+    let legacy: PendingNoisJob =
+        from_slice(br#"{"job":{"crop_events":{"owner":"player"}},"created_at":1}"#).unwrap();
+    assert!(legacy.funds.is_empty());
+
+    env.block.height += 100;
+    let settle = ExecuteMsg::SettleNoisJob {
+        job_id: "2".to_string(),
+    };
+    let res = execute(deps.as_mut(), env.clone(), anyone.clone(), settle.clone()).unwrap();
+    // Fallback yield is the minimum seed count of a sunflower.
+    assert_eq!(res.messages.len(), 2);
+    assert!(pending_jobs(deps.as_ref()).is_empty());
+
+    let err = execute(deps.as_mut(), env, anyone, settle).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownNoisJob {
+            job_id: "2".to_string()
+        }
+    );
+
+    for job_timeout in [0, params::MAX_NOIS_JOB_TIMEOUT + 1] {
+        let msg = ExecuteMsg::UpdateNoisConfig {
+            config: NoisConfig { job_timeout },
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidNoisConfig { .. }));
+    }
+}
+
+#[test]
+fn crop_events() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: Some("nois_proxy".to_string()),
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let config = CropEventsConfig {
+        cooldown: 50,
+        drought: 0,
+        pests: 100,
+        bumper_crop: 0,
+        golden_mutation: 100,
+    };
+    let msg = ExecuteMsg::UpdateCropEventsConfig {
+        config: CropEventsConfig {
+            drought: 101,
+            ..config.clone()
+        },
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidCropEventsConfig { .. }));
+
+    let msg = ExecuteMsg::UpdateCropEventsConfig {
+        config: CropEventsConfig {
+            cooldown: u64::MAX,
+            ..config.clone()
+        },
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidCropEventsConfig { .. }));
+
+    let msg = ExecuteMsg::UpdateCropEventsConfig { config };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+
+    let info = mock_info(player, &[coin(300, "unois")]);
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        info.clone(),
+        ExecuteMsg::RollCropEvents {},
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "1");
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        info,
+        ExecuteMsg::RollCropEvents {},
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::CropEventsCooldown {
+            available_at: env.block.height + 50
+        }
+    );
+
+    let callback = NoisCallback {
+        job_id: "1".to_string(),
+        published: Timestamp::from_seconds(1),
+        randomness: HexBinary::from([7u8; 32]),
+    };
+    let msg = ExecuteMsg::NoisReceive {
+        callback: callback.clone(),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("anyone", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let msg = ExecuteMsg::NoisReceive { callback };
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("nois_proxy", &[]),
+        msg,
+    )
+    .unwrap();
+
+    let farm = get_farm_profile(player, deps.as_ref());
+    let plant = farm.plots[0][0].plant.clone().unwrap();
+    assert_eq!(
+        plant.effects,
+        vec![CropEvent::Pests, CropEvent::GoldenMutation]
+    );
+
+    // Pests hold the plant back by one growth period, it would be at stage 2 otherwise.
+    let mut later = env;
+    later.block.height += 10;
+    let msg = QueryMsg::GetFarmProfile {
+        address: player.to_string(),
+    };
+    let farm: FarmProfileDto = from_binary(&query(deps.as_ref(), later, msg).unwrap()).unwrap();
+    assert_eq!(farm.plots[0][0].plant.as_ref().unwrap().current_stage, 1);
+}
+
+#[test]
+fn leaderboard_pagination() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    for (addr, amount) in [("alice", 5), ("bob", 20), ("carol", 5), ("dave", 10)] {
+        let mut pts = Points::new(addr.to_string());
+        pts.add(&"sunflower".parse().unwrap(), amount);
+        points().save(&mut deps.storage, addr, &pts).unwrap();
+    }
+
+    let leaderboard = |start_after: Option<&str>, limit: Option<u32>| -> Vec<LeaderboardEntry> {
+        let msg = QueryMsg::Leaderboard {
+            plant_type: None,
+            start_after: start_after.map(|addr| addr.to_string()),
+            limit,
+        };
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+    };
+
+    let first_page = leaderboard(None, Some(2));
+    assert_eq!(
+        first_page,
+        vec![
+            LeaderboardEntry {
+                rank: 1,
+                address: "bob".to_string(),
+                total: 20,
+            },
+            LeaderboardEntry {
+                rank: 2,
+                address: "dave".to_string(),
+                total: 10,
+            },
+        ]
+    );
+
+    let second_page = leaderboard(Some("dave"), Some(2));
+    let ranks: Vec<(u64, String)> = second_page
+        .into_iter()
+        .map(|entry| (entry.rank, entry.address))
+        .collect();
+    assert_eq!(
+        ranks,
+        vec![(3, "carol".to_string()), (4, "alice".to_string())]
+    );
+    assert!(leaderboard(Some("alice"), None).is_empty());
+
+    let msg = QueryMsg::Leaderboard {
+        plant_type: None,
+        start_after: Some("nobody".to_string()),
+        limit: None,
+    };
+    let err = query(deps.as_ref(), env.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err("nobody is not on the leaderboard")
+    );
+
+    let msg = QueryMsg::Rank {
+        address: "carol".to_string(),
+        plant_type: None,
+    };
+    let rank: Option<LeaderboardEntry> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(rank.unwrap().rank, 3);
+
+    let msg = QueryMsg::Rank {
+        address: "nobody".to_string(),
+        plant_type: None,
+    };
+    let rank: Option<LeaderboardEntry> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(rank, None);
+
+    for i in 0..120 {
+        let addr = format!("player{i}");
+        let mut pts = Points::new(addr.clone());
+        pts.add(&"wheat".parse().unwrap(), 1);
+        points().save(&mut deps.storage, &addr, &pts).unwrap();
+    }
+    for limit in [None, Some(500)] {
+        let msg = QueryMsg::Leaderboard {
+            plant_type: None,
+            start_after: None,
+            limit,
+        };
+        let page: Vec<LeaderboardEntry> =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        assert_eq!(page.len(), 100);
+    }
+}
+
+#[test]
+fn crop_leaderboard() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    // Wheat points from before the per crop leaderboard existed.
+    let mut pts = Points::new("farmer".to_string());
+    pts.add(&"wheat".parse().unwrap(), 50);
+    points().save(&mut deps.storage, "farmer", &pts).unwrap();
+    migrate_legacy(deps.as_mut(), env.clone());
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+    let env = grow_sunflower(player, 0, 0, env, &mut deps.as_mut());
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    execute(deps.as_mut(), env.clone(), mock_info(player, &[]), msg).unwrap();
+
+    let leaderboard = |plant_type: Option<&str>| -> Vec<(u64, String, u64)> {
+        let msg = QueryMsg::Leaderboard {
+            plant_type: plant_type.map(|name| name.to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let entries: Vec<LeaderboardEntry> =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        entries
+            .into_iter()
+            .map(|entry| (entry.rank, entry.address, entry.total))
+            .collect()
+    };
+
+    assert_eq!(
+        leaderboard(None),
+        vec![(1, "farmer".to_string(), 50), (2, "player".to_string(), 1)]
+    );
+    assert_eq!(
+        leaderboard(Some("Sunflower")),
+        vec![(1, "player".to_string(), 1)]
+    );
+    assert_eq!(
+        leaderboard(Some("wheat")),
+        vec![(1, "farmer".to_string(), 50)]
+    );
+
+    let msg = QueryMsg::Rank {
+        address: "farmer".to_string(),
+        plant_type: Some("sunflower".to_string()),
+    };
+    let rank: Option<LeaderboardEntry> =
+        from_binary(&query(deps.as_ref(), env, msg).unwrap()).unwrap();
+    assert_eq!(rank, None);
+}
+
+#[test]
+fn seasons() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let create = |start: u64, end: u64| ExecuteMsg::CreateSeason { start, end };
+    let block = env.block.height;
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &[]),
+        create(block, block + 50),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let admin = mock_info("creator", &[]);
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        create(block, block + 50),
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        create(block + 49, block + 100),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::InvalidSeason { .. }));
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        admin,
+        create(block + 50, block + 100),
+    )
+    .unwrap();
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+    let mut env = grow_sunflower(player, 0, 0, env, &mut deps.as_mut());
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    execute(deps.as_mut(), env.clone(), mock_info(player, &[]), msg).unwrap();
+
+    let msg = QueryMsg::CurrentSeason {};
+    let season: Option<Season> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(season.unwrap().id, 1);
+
+    let close = ExecuteMsg::CloseSeason { season_id: 1 };
+    let anyone = mock_info("anyone", &[]);
+    let err = execute(deps.as_mut(), env.clone(), anyone.clone(), close.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeasonNotEnded {
+            season_id: 1,
+            end: block + 50
+        }
+    );
+
+    env.block.height = block + 50;
+    execute(deps.as_mut(), env.clone(), anyone.clone(), close.clone()).unwrap();
+    let err = execute(deps.as_mut(), env.clone(), anyone, close).unwrap_err();
+    assert_eq!(err, ContractError::SeasonClosed { season_id: 1 });
+
+    let msg = QueryMsg::SeasonResults {
+        season_id: 1,
+        start_after: None,
+        limit: None,
+    };
+    let results: Vec<LeaderboardEntry> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        results,
+        vec![LeaderboardEntry {
+            rank: 1,
+            address: player.to_string(),
+            total: 1,
+        }]
+    );
+
+    // The next season starts from zero while the all time leaderboard keeps the points.
+    let msg = QueryMsg::SeasonLeaderboard {
+        season_id: 2,
+        start_after: None,
+        limit: None,
+    };
+    let standings: Vec<LeaderboardEntry> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert!(standings.is_empty());
+    assert_eq!(points().load(&deps.storage, player).unwrap().total(), 1);
+}
+
+#[test]
+fn prize_distribution() {
+    let (mut deps, mut env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let admin = mock_info("creator", &[]);
+    let block = env.block.height;
+    let msg = ExecuteMsg::CreateSeason {
+        start: block,
+        end: block + 10,
+    };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+    for (addr, total) in [("alice", 30), ("bob", 20), ("carol", 10)] {
+        let pts = SeasonPoints {
+            season_id: 1,
+            addr: addr.to_string(),
+            total,
+        };
+        season_points()
+            .save(&mut deps.storage, (1, addr), &pts)
+            .unwrap();
+    }
+
+    let fund = ExecuteMsg::FundPrizePool {};
+    let funds = [coin(1000, "ujuno")];
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("creator", &funds),
+        fund.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PrizeConfigMissing {});
+
+    let msg = ExecuteMsg::UpdatePrizeConfig {
+        config: PrizeConfig {
+            denom: "ujuno".to_string(),
+            shares: vec![6000, 3000, 2000],
+        },
+    };
+    let err = execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::InvalidPrizeConfig { .. }));
+
+    let msg = ExecuteMsg::UpdatePrizeConfig {
+        config: PrizeConfig {
+            denom: "ujuno".to_string(),
+            shares: vec![6000, 3000],
+        },
+    };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("creator", &[coin(1000, "uatom")]),
+        fund.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidPrizeFunds {
+            denom: "ujuno".to_string()
+        }
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("creator", &funds),
+        fund,
+    )
+    .unwrap();
+
+    let distribute = ExecuteMsg::DistributePrizes { season_id: Some(1) };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeasonNotEnded {
+            season_id: 1,
+            end: block + 10
+        }
+    );
+
+    env.block.height = block + 10;
+    let msg = ExecuteMsg::CloseSeason { season_id: 1 };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        distribute.clone(),
+    )
+    .unwrap();
+    let sends: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        sends,
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: vec![coin(600, "ujuno")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: vec![coin(300, "ujuno")],
+            }),
+        ]
+    );
+
+    let msg = QueryMsg::PrizePayouts { season_id: Some(1) };
+    let payouts: Vec<PrizePayout> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(payouts.len(), 2);
+    assert_eq!(payouts[1].rank, 2);
+
+    let pool: Uint128 =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PrizePool {}).unwrap()).unwrap();
+    assert_eq!(pool, Uint128::new(100));
+
+    let err = execute(deps.as_mut(), env, admin, distribute).unwrap_err();
+    assert_eq!(err, ContractError::PrizesDistributed { season_id: 1 });
+}
+
+#[test]
+fn all_time_prizes() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let admin = mock_info("creator", &[]);
+    let msg = ExecuteMsg::UpdatePrizeConfig {
+        config: PrizeConfig {
+            denom: "ujuno".to_string(),
+            shares: vec![6000, 3000],
+        },
+    };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+    // An empty pool or leaderboard pays nothing and records nothing.
+    let distribute = ExecuteMsg::DistributePrizes { season_id: None };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPrizes {});
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("creator", &[coin(1000, "ujuno")]),
+        ExecuteMsg::FundPrizePool {},
+    )
+    .unwrap();
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        distribute.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoPrizes {});
+
+    for (addr, total) in [("alice", 10), ("bob", 30), ("carol", 20)] {
+        let mut pts = Points::new(addr.to_string());
+        pts.add(&"sunflower".parse().unwrap(), total);
+        points().save(&mut deps.storage, addr, &pts).unwrap();
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        admin.clone(),
+        distribute.clone(),
+    )
+    .unwrap();
+    let sends: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        sends,
+        vec![
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "bob".to_string(),
+                amount: vec![coin(600, "ujuno")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "carol".to_string(),
+                amount: vec![coin(300, "ujuno")],
+            }),
+        ]
+    );
+
+    let msg = QueryMsg::PrizePayouts { season_id: None };
+    let payouts: Vec<PrizePayout> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(payouts.len(), 2);
+    assert_eq!(payouts[0].address, "bob");
+
+    let err = execute(deps.as_mut(), env, admin, distribute).unwrap_err();
+    assert_eq!(err, ContractError::AllTimePrizesDistributed {});
+}
+
+#[test]
+fn crop_tokens() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let msg = ExecuteMsg::SetCropToken {
+        plant_type: "carrot".to_string(),
+        token: Some("carrot_token".to_string()),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownPlant {
+            name: "carrot".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::SetCropToken {
+        plant_type: "Sunflower".to_string(),
+        token: Some("sunflower_token".to_string()),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let msg = QueryMsg::CropTokens {
+        start_after: None,
+        limit: None,
+    };
+    let tokens: Vec<CropTokenResponse> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        tokens,
+        vec![CropTokenResponse {
+            plant_type: "sunflower".to_string(),
+            token: Addr::unchecked("sunflower_token"),
+        }]
+    );
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+    let env = grow_sunflower(player, 0, 0, env, &mut deps.as_mut());
+
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    let res = execute(deps.as_mut(), env, mock_info(player, &[]), msg).unwrap();
+    let mint = res.messages.last().unwrap().msg.clone();
+    assert_eq!(
+        mint,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "sunflower_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: player.to_string(),
+                amount: Uint128::new(1_000_000),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn seed_shop() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let buy = ExecuteMsg::BuySeeds {
+        plant_type: "sunflower".to_string(),
+        amount: 3,
+    };
+    let funds = [coin(300, "ujuno")];
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &funds),
+        buy.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::SeedsNotForSale {
+            plant_type: "sunflower".to_string()
+        }
+    );
+
+    let price = SeedPrice {
+        price: coin(100, "ujuno"),
+        komple: KomplePlant {
+            metadata_id: 1,
+            collection_id: 2,
+        },
+    };
+    let msg = ExecuteMsg::SetSeedPrice {
+        plant_type: "sunflower".to_string(),
+        price: Some(price.clone()),
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let msg = ExecuteMsg::SetSeedPrice {
+        plant_type: "wheat".to_string(),
+        price: Some(SeedPrice {
+            price: coin(0, "ujuno"),
+            ..price.clone()
+        }),
+    };
+    let err = execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrice {});
+
+    let msg = QueryMsg::SeedPrices {
+        start_after: None,
+        limit: None,
+    };
+    let prices: Vec<SeedPriceResponse> =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(
+        prices,
+        vec![SeedPriceResponse {
+            plant_type: "sunflower".to_string(),
+            price: price.clone(),
+        }]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &[coin(200, "ujuno")]),
+        buy.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFunds {
+            required: Some(coin(300, "ujuno"))
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &funds),
+        buy.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages.len(), 4);
+    assert_eq!(
+        res.messages[3].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: "creator".to_string(),
+            amount: funds.to_vec(),
+        })
+    );
+
+    let msg = ExecuteMsg::SetSeedPrice {
+        plant_type: "sunflower".to_string(),
+        price: Some(SeedPrice {
+            price: coin(u128::MAX, "ujuno"),
+            ..price
+        }),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+    let err = execute(deps.as_mut(), env, mock_info("player", &funds), buy).unwrap_err();
+    assert!(matches!(err, ContractError::Overflow(_)));
+}
+
+#[test]
+fn marketplace() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let seller = "seller";
+    let list = |price: Coin| {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: seller.to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary(&Cw721HookMsg::List { price }).unwrap(),
+        })
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(collection_addr, &[]),
+        list(coin(0, "ujuno")),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidPrice {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(collection_addr, &[]),
+        list(coin(1000, "ujuno")),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "1");
+
+    let msg = ExecuteMsg::SetCropToken {
+        plant_type: "wheat".to_string(),
+        token: Some("wheat_token".to_string()),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let list_tokens = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "farmer".to_string(),
+        amount: Uint128::new(500),
+        msg: to_binary(&Cw20HookMsg::List {
+            price: coin(50, "ujuno"),
+        })
+        .unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("other_token", &[]),
+        list_tokens.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownCropToken {
+            address: "other_token".to_string()
+        }
+    );
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("wheat_token", &[]),
+        list_tokens,
+    )
+    .unwrap();
+
+    let get_listings = |seller: Option<&str>, plant_type: Option<&str>| -> Vec<u64> {
+        let msg = QueryMsg::Listings {
+            seller: seller.map(|s| s.to_string()),
+            plant_type: plant_type.map(|p| p.to_string()),
+            start_after: None,
+            limit: None,
+        };
+        let listings: Vec<Listing> =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        listings.into_iter().map(|listing| listing.id).collect()
+    };
+    assert_eq!(get_listings(None, None), vec![1, 2]);
+    assert_eq!(get_listings(Some(seller), None), vec![1]);
+    assert_eq!(get_listings(None, Some("Wheat")), vec![2]);
+    assert!(get_listings(Some(seller), Some("wheat")).is_empty());
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("buyer", &[]),
+        ExecuteMsg::CancelListing { listing_id: 2 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("farmer", &[]),
+        ExecuteMsg::CancelListing { listing_id: 2 },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "wheat_token".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "farmer".to_string(),
+                amount: Uint128::new(500),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+    );
+
+    let buy = ExecuteMsg::BuyListing { listing_id: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("buyer", &[coin(999, "ujuno")]),
+        buy.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFunds {
+            required: Some(coin(1000, "ujuno"))
+        }
+    );
+
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("buyer", &[coin(1000, "ujuno")]),
+        buy.clone(),
+    )
+    .unwrap();
+    let msgs: Vec<CosmosMsg> = res.messages.into_iter().map(|m| m.msg).collect();
+    assert_eq!(
+        cw721_msg(&msgs[0], collection_addr),
+        Cw721ExecuteMsg::TransferNft {
+            recipient: "buyer".to_string(),
+            token_id: "1".to_string(),
+        }
+    );
+    // Default fee is 2.5%.
+    assert_eq!(
+        msgs[1..],
+        [
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: seller.to_string(),
+                amount: vec![coin(975, "ujuno")],
+            }),
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "creator".to_string(),
+                amount: vec![coin(25, "ujuno")],
+            }),
+        ]
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info("buyer", &[coin(1000, "ujuno")]),
+        buy,
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::UnknownListing { listing_id: 1 });
+}
+
+#[test]
+fn fertilizers() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    till(player, 0, 1, deps.as_mut());
+    send_seed_token(
+        player,
+        collection_addr,
+        "1",
+        0,
+        0,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+    send_seed_token(
+        player,
+        collection_addr,
+        "2",
+        0,
+        1,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+
+    let fertilizer = |kind: &'static str| {
+        move |wasm_query: &WasmQuery| {
+            komple_wasm_query(
+                wasm_query,
+                collection_addr,
+                vec![Trait {
+                    trait_type: "fertilizer".into(),
+                    value: kind.into(),
+                }],
+            )
+        }
+    };
+    let fertilize = |token_id: &str, x: u8, y: u8| {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: player.to_string(),
+            token_id: token_id.to_string(),
+            msg: to_binary(&Cw721HookMsg::Fertilize { x, y }).unwrap(),
+        })
+    };
+    let collection = mock_info(collection_addr, &[]);
+
+    deps.querier.update_wasm(fertilizer("growth"));
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        collection.clone(),
+        fertilize("3", 0, 0),
+    )
+    .unwrap();
+    assert_eq!(
+        cw721_msg(&res.messages[0].msg, collection_addr),
+        Cw721ExecuteMsg::Burn {
+            token_id: "3".to_string()
+        }
+    );
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        collection.clone(),
+        fertilize("4", 0, 0),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PlantNotFertilizable {
+            x: 0,
+            y: 0,
+            reason: FertilizeBlockReason::AlreadyFertilized
+        }
+    );
+
+    // The plant skipped to the next stage and has to be watered again.
+    let plant = get_farm_profile(player, deps.as_ref()).plots[0][0]
+        .plant
+        .clone()
+        .unwrap();
+    assert_eq!(plant.current_stage, 2);
+    assert!(plant.can_water);
+    assert_eq!(plant.fertilizers, vec![Fertilizer::Growth]);
+
+    let mut later = env.clone();
+    later.block.height += 10;
+    let err = execute(
+        deps.as_mut(),
+        later.clone(),
+        collection.clone(),
+        fertilize("5", 0, 1),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PlantNotFertilizable {
+            x: 0,
+            y: 1,
+            reason: FertilizeBlockReason::NeedsWater
+        }
+    );
+
+    deps.querier.update_wasm(fertilizer("yield"));
+    execute(
+        deps.as_mut(),
+        later,
+        collection.clone(),
+        fertilize("5", 0, 1),
+    )
+    .unwrap();
+    let plant = get_farm_profile(player, deps.as_ref()).plots[0][1]
+        .plant
+        .clone()
+        .unwrap();
+    assert_eq!(plant.fertilizers, vec![Fertilizer::Yield]);
+
+    deps.querier.update_wasm(fertilizer("compost"));
+    let err = execute(deps.as_mut(), env, collection, fertilize("6", 0, 1)).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::UnknownFertilizer {
+            name: "compost".to_string()
+        }
+    );
+}
+
+#[test]
+fn sprinklers() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    let info = mock_info(player, &[]);
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+
+    let msg = ExecuteMsg::BuildSprinkler { x: 1, y: 1 };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NotEnoughPoints {
+            received: 0,
+            required: 20,
+            max_amount: None
+        }
+    );
+
+    let mut pts = Points::new(player.to_string());
+    pts.add(&"sunflower".parse().unwrap(), 25);
+    points().save(&mut deps.storage, player, &pts).unwrap();
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(points().load(&deps.storage, player).unwrap().available(), 5);
+
+    let msg = ExecuteMsg::BuildSprinkler { x: 0, y: 0 };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::PlotNotMeadow { x: 0, y: 0 });
+
+    // The plant is never watered by hand but keeps growing.
+    let mut later = env.clone();
+    later.block.height += 40;
+    let farm = get_farm_profile(player, deps.as_ref());
+    assert!(farm.plots[0][0].irrigated);
+    assert!(!farm.plots[3][3].irrigated);
+    assert_eq!(farm.plots[1][1].r#type, SlotType::Sprinkler);
+
+    let msg = ExecuteMsg::WaterPlant { x: 0, y: 0 };
+    let err = execute(deps.as_mut(), later.clone(), info.clone(), msg).unwrap_err();
+    assert!(matches!(err, ContractError::PlantNotWaterable { .. }));
+
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    execute(deps.as_mut(), later.clone(), info.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::RemoveSprinkler { x: 1, y: 1 };
+    execute(deps.as_mut(), later.clone(), info.clone(), msg.clone()).unwrap();
+    let farm = FarmProfile::load_all(&deps.storage, player)
+        .unwrap()
+        .unwrap();
+    assert_eq!(farm.get_plot(1, 1).unwrap().r#type, SlotType::Meadow);
+    assert_eq!(farm.irrigated_since(0, 0), None);
+
+    let err = execute(deps.as_mut(), later, info, msg).unwrap_err();
+    assert_eq!(err, ContractError::NoSprinkler { x: 1, y: 1 });
+}
+
+#[test]
+fn boosted_yield_saturates() {
+    let plant = Plant {
+        effects: vec![CropEvent::BumperCrop, CropEvent::GoldenMutation],
+        fertilizers: vec![Fertilizer::Yield],
+        ..arbitrary_plant(0, 10, 5, 1, 0)
+    };
+    let config = PlantConfig {
+        stages: 5,
+        growth_period: 10,
+        watering_tolerance: 0,
+        min_seeds: u32::MAX - 1,
+        max_seeds: u32::MAX,
+        points: u64::MAX,
+        produce: Uint128::zero(),
+    };
+
+    assert_eq!(plant.seed_yield(&config), (u32::MAX, u32::MAX));
+    assert_eq!(plant.harvest_points(&config), u64::MAX);
+}
+
+#[test]
+fn sprinkler_irrigation_is_kept() {
+    let mut farm = FarmProfile::new(0, ClockMode::BlockHeight);
+    let plant = Plant {
+        effects: vec![],
+        fertilizers: vec![],
+        ..arbitrary_plant(0, 10, 5, 1, 0)
+    };
+    let field = |plant: Plant| Slot {
+        r#type: SlotType::Field,
+        plant: Some(plant),
+        created_at: 0,
+    };
+    let is_dead = |farm: &FarmProfile, x: usize, y: usize, block: u64| {
+        let plant = farm.get_plot(x, y).unwrap().plant.unwrap();
+        plant.is_dead(block, farm.irrigated_since(x, y))
+    };
+
+    // Removing the sprinkler keeps the stages it watered.
+    farm.set_plot(0, 0, field(plant.clone())).unwrap();
+    farm.build_sprinkler(1, 1, 0).unwrap();
+    assert!(!is_dead(&farm, 0, 0, 35));
+    farm.remove_sprinkler(1, 1, 35).unwrap();
+    assert!(!is_dead(&farm, 0, 0, 35));
+    assert_eq!(farm.get_plot(0, 0).unwrap().plant.unwrap().watered, 4);
+
+    // Building a sprinkler next to a dead plant doesn't revive it.
+    let dry = Plant {
+        watered: 0,
+        ..plant
+    };
+    farm.set_plot(3, 3, field(dry)).unwrap();
+    assert!(is_dead(&farm, 3, 3, 30));
+    farm.build_sprinkler(4, 4, 30).unwrap();
+    assert!(is_dead(&farm, 3, 3, 30));
+    assert!(is_dead(&farm, 3, 3, 40));
+}
+
+#[test]
+fn batch_actions() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let player = "player";
+    let info = mock_info(player, &[]);
+    init_farm(player, deps.as_mut());
+
+    // A failing action reverts the whole batch.
+    let msg = ExecuteMsg::Batch {
+        actions: vec![
+            FarmAction::Till { x: 0, y: 0 },
+            FarmAction::Till { x: 0, y: 0 },
+        ],
+        skip_failures: false,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap_err();
+    assert_eq!(err, ContractError::PlotNotTillable { x: 0, y: 0 });
+    assert_eq!(
+        get_farm_profile(player, deps.as_ref()).plots[0][0].r#type,
+        SlotType::Meadow
+    );
+
+    let msg = ExecuteMsg::Batch {
+        actions: vec![
+            FarmAction::Till { x: 0, y: 0 },
+            FarmAction::Till { x: 0, y: 1 },
+        ],
+        skip_failures: false,
+    };
+    execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    send_seed_token(
+        player,
+        collection_addr,
+        "1",
+        0,
+        0,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+    send_seed_token(
+        player,
+        collection_addr,
+        "2",
+        0,
+        1,
+        env.clone(),
+        deps.as_mut(),
+    )
+    .unwrap();
+
+    let mut env = env;
+    for _ in 0..4 {
+        env.block.height += 10;
+        let msg = ExecuteMsg::Batch {
+            actions: vec![
+                FarmAction::Water { x: 0, y: 0 },
+                FarmAction::Water { x: 0, y: 1 },
+            ],
+            skip_failures: false,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    }
+
+    let harvest = ExecuteMsg::Batch {
+        actions: vec![
+            FarmAction::Harvest { x: 0, y: 0 },
+            FarmAction::Harvest { x: 0, y: 1 },
+            FarmAction::Harvest { x: 5, y: 5 },
+        ],
+        skip_failures: true,
+    };
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(player, &[coin(4, "ujuno")]),
+        harvest.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidBatch {
+            reason: "funds must split evenly between the harvests".to_string()
+        }
+    );
+
+    let msg = ExecuteMsg::Batch {
+        actions: vec![
+            FarmAction::Harvest { x: 5, y: 5 },
+            FarmAction::Harvest { x: 0, y: 0 },
+        ],
+        skip_failures: false,
+    };
+    let err = execute(deps.as_mut(), env.clone(), info, msg).unwrap_err();
+    assert_eq!(err, ContractError::NoPlant { x: 5, y: 5 });
+
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(player, &[coin(3, "ujuno")]),
+        harvest,
+    )
+    .unwrap();
+    assert_eq!(res.attributes[1].value, "2");
+    assert_eq!(res.attributes[2].value, "1");
+
+    let results: Vec<FarmActionResult> = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(results[0].error, None);
+    assert_eq!(results[1].error, None);
+    assert_eq!(
+        results[2].error,
+        Some("Plot [5, 5] does not contain a plant".to_string())
+    );
+
+    // Without a Nois proxy no randomness is paid for, the funds are refunded.
+    assert_eq!(
+        res.messages.last().unwrap().msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: player.to_string(),
+            amount: vec![coin(3, "ujuno")]
+        })
+    );
+
+    let pts = points().load(&deps.storage, player).unwrap();
+    assert_eq!(pts.total(), 2);
+    let farm = FarmProfile::load_all(&deps.storage, player)
+        .unwrap()
+        .unwrap();
+    assert!(farm.get_plot(0, 0).unwrap().plant.is_none());
+}
+
+#[test]
+fn failed_batch_harvest_keeps_the_plant() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    let plant = Plant {
+        komple: Some(KomplePlant {
+            metadata_id: 1,
+            collection_id: 1,
+        }),
+        effects: vec![],
+        fertilizers: vec![],
+        ..arbitrary_plant(env.block.height - 45, 10, 5, 5, 0)
+    };
+    let slot = Slot {
+        r#type: SlotType::Field,
+        plant: Some(plant),
+        created_at: env.block.height - 45,
+    };
+    FARM_PLOTS
+        .save(deps.as_mut().storage, (player, 0, 0), &slot)
+        .unwrap();
+
+    // Minting the seeds fails without a Komple mint module.
+    let msg = ExecuteMsg::Batch {
+        actions: vec![FarmAction::Harvest { x: 0, y: 0 }],
+        skip_failures: true,
+    };
+    let res = execute(deps.as_mut(), env, mock_info(player, &[]), msg).unwrap();
+    let results: Vec<FarmActionResult> = from_binary(&res.data.unwrap()).unwrap();
+    assert!(results[0].error.is_some());
+
+    assert_eq!(
+        FARM_PLOTS.load(&deps.storage, (player, 0, 0)).unwrap(),
+        slot
+    );
+    assert!(points().may_load(&deps.storage, player).unwrap().is_none());
+}
+
+#[test]
+fn farm_profile_migration() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let mut plots = vec![];
+    for x in 0..9 {
+        let mut row = vec![];
+        for y in 0..9 {
+            let r#type = match (x, y) {
+                (0, 0) => SlotType::Field,
+                (1, 1) => SlotType::Sprinkler,
+                _ => SlotType::Meadow,
+            };
+            row.push(Slot {
+                r#type,
+                plant: None,
+                created_at: env.block.height,
+            });
+        }
+        plots.push(row);
+    }
+    let layout = FarmLayout { plots };
+
+    // Farms stored as a single value before the plots were stored separately.
+    let legacy_farms: Map<&str, FarmLayout> = Map::new("farm_profiles");
+    legacy_farms
+        .save(&mut deps.storage, "legacy", &layout)
+        .unwrap();
+
+    let msg = ExecuteMsg::SetupFarm {
+        farm: layout,
+        addr: Addr::unchecked("fresh"),
+    };
+    execute(deps.as_mut(), env.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    migrate_legacy(deps.as_mut(), env.clone());
+    assert!(legacy_farms
+        .may_load(&deps.storage, "legacy")
+        .unwrap()
+        .is_none());
+
+    let profile = |address: &str| {
+        let msg = QueryMsg::GetFarmProfile {
+            address: address.to_string(),
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap()
+    };
+    assert_eq!(profile("legacy"), profile("fresh"));
+
+    let farm = get_farm_profile("legacy", deps.as_ref());
+    assert_eq!(farm.size, 9);
+    assert!(farm.plots[0][0].irrigated);
+    assert_eq!(farm.plots[1][1].r#type, SlotType::Sprinkler);
+
+    till("legacy", 8, 8, deps.as_mut());
+    let farm = get_farm_profile("legacy", deps.as_ref());
+    assert_eq!(farm.plots[8][8].r#type, SlotType::Field);
+    assert_eq!(farm.plots[0][0].r#type, SlotType::Field);
+
+    // Once converted, later migrations skip the legacy storage.
+    let legacy = FarmLayout {
+        plots: vec![vec![Slot {
+            r#type: SlotType::Meadow,
+            plant: None,
+            created_at: env.block.height,
+        }]],
+    };
+    legacy_farms
+        .save(&mut deps.storage, "late", &legacy)
+        .unwrap();
+    migrate(deps.as_mut(), env, MigrateMsg {}).unwrap();
+    assert!(legacy_farms.has(&deps.storage, "late"));
+}
+
+#[test]
+fn compact_watering() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let height = env.block.height;
+    // A plant stored before the watered stages were counted.
+    let legacy_plant: Plant = from_slice(
+        format!(
+            r#"{{"type":"sunflower","stages":5,"growth_period":10,"created_at":{0},"watered_at":[{0},{1}],"komple":null}}"#,
+            height,
+            height + 10
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(legacy_plant.watered, 0);
+
+    let slot = Slot {
+        r#type: SlotType::Field,
+        plant: Some(legacy_plant),
+        created_at: height,
+    };
+    FARMS
+        .save(&mut deps.storage, "legacy", &FarmInfo { size: 1 })
+        .unwrap();
+    FARM_PLOTS
+        .save(&mut deps.storage, ("legacy", 0, 0), &slot)
+        .unwrap();
+    migrate_legacy(deps.as_mut(), env.clone());
+
+    let plant = FARM_PLOTS
+        .load(&deps.storage, ("legacy", 0, 0))
+        .unwrap()
+        .plant
+        .unwrap();
+    assert_eq!(plant.watered, 2);
+    assert_eq!(plant.last_watered_at, height + 10);
+    assert!(plant.watered_at.is_empty());
+
+    // Farms set up with the old plant shape are compacted as well.
+    let mut later = env;
+    later.block.height += 10;
+    let msg = ExecuteMsg::SetupFarm {
+        farm: FarmLayout {
+            plots: vec![vec![slot]],
+        },
+        addr: Addr::unchecked("fresh"),
+    };
+    execute(deps.as_mut(), later.clone(), mock_info("creator", &[]), msg).unwrap();
+
+    let msg = QueryMsg::GetFarmProfile {
+        address: "fresh".to_string(),
+    };
+    let farm: FarmProfileDto = from_binary(&query(deps.as_ref(), later, msg).unwrap()).unwrap();
+    let plant = farm.plots[0][0].plant.clone().unwrap();
+    assert_eq!(plant.watered_stages, 2);
+    assert_eq!(plant.last_watered_at, height + 10);
+    assert_eq!(plant.watered_at, vec![height + 10, height + 10]);
+    assert!(!plant.can_water);
+    assert!(!plant.is_dead);
+}
+
+#[test]
+fn seconds_clock() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: Some(get_komple_addrs().mint.to_string()),
+        nois_proxy: None,
+        clock_mode: Some(ClockMode::Seconds),
+    }));
+
+    let collection_addr = "collection_addr";
+    deps.querier.update_wasm(move |wasm_query| {
+        komple_seed_wasm_query(wasm_query, collection_addr, "sunflower")
+    });
+
+    let clock: ClockMode =
+        from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::ClockMode {}).unwrap()).unwrap();
+    assert_eq!(clock, ClockMode::Seconds);
+
+    let msg = QueryMsg::PlantType {
+        name: "sunflower".to_string(),
+    };
+    let config: PlantConfig =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    assert_eq!(config.growth_period, 60);
+
+    let player = "player";
+    init_farm(player, deps.as_mut());
+    till(player, 0, 0, deps.as_mut());
+    till(player, 0, 1, deps.as_mut());
+    send_seed(player, collection_addr, 0, 0, env.clone(), deps.as_mut()).unwrap();
+
+    // Blocks alone do not make the plant grow.
+    let mut later = env.clone();
+    later.block.height += 100;
+    let msg = ExecuteMsg::WaterPlant { x: 0, y: 0 };
+    let err = execute(
+        deps.as_mut(),
+        later.clone(),
+        mock_info(player, &[]),
+        msg.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::PlantNotWaterable { .. }));
+
+    let profile = |env: Env, deps: Deps| -> FarmProfileDto {
+        let msg = QueryMsg::GetFarmProfile {
+            address: player.to_string(),
+        };
+        from_binary(&query(deps, env, msg).unwrap()).unwrap()
+    };
+    assert_eq!(
+        profile(later.clone(), deps.as_ref()).plots[0][1].r#type,
+        SlotType::Field
+    );
+
+    later.block.time = later.block.time.plus_seconds(61);
+    execute(deps.as_mut(), later.clone(), mock_info(player, &[]), msg).unwrap();
+
+    let farm = profile(later.clone(), deps.as_ref());
+    assert_eq!(farm.clock_mode, ClockMode::Seconds);
+    assert_eq!(farm.blocks, later.block.time.seconds());
+    assert_eq!(farm.plots[0][0].plant.as_ref().unwrap().current_stage, 2);
+    assert_eq!(farm.plots[0][1].r#type, SlotType::Meadow);
+}
+
+#[test]
+fn setup_farm_validation() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let height = env.block.height;
+    let meadow = Slot {
+        r#type: SlotType::Meadow,
+        plant: None,
+        created_at: height,
+    };
+    let plant: Plant = from_slice(
+        format!(
+            r#"{{"type":"sunflower","stages":5,"growth_period":10,"created_at":{},"watered_at":[1,1,1,1,1]}}"#,
+            height
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    let field = Slot {
+        r#type: SlotType::Field,
+        plant: Some(plant.clone()),
+        created_at: height,
+    };
+
+    let setup = |plots: Vec<Vec<Slot>>, deps: DepsMut| {
+        let msg = ExecuteMsg::SetupFarm {
+            farm: FarmLayout { plots },
+            addr: Addr::unchecked("player"),
+        };
+        execute(deps, env.clone(), mock_info("creator", &[]), msg)
+    };
+    let invalid = |reason: &str| ContractError::InvalidFarm {
+        reason: reason.to_string(),
+    };
+
+    let err = setup(vec![], deps.as_mut()).unwrap_err();
+    assert_eq!(err, invalid("size must be between 1 and 255"));
+
+    let err = setup(
+        vec![vec![meadow.clone(), meadow.clone()], vec![meadow.clone()]],
+        deps.as_mut(),
+    )
+    .unwrap_err();
+    assert_eq!(err, invalid("row 1 must have 2 plots"));
+
+    let future = Slot {
+        created_at: height + 1,
+        ..meadow.clone()
+    };
+    let err = setup(vec![vec![future]], deps.as_mut()).unwrap_err();
+    assert_eq!(err, invalid("plot [0, 0] is created in the future"));
+
+    let future = Slot {
+        plant: Some(Plant {
+            created_at: height + 1,
+            ..plant.clone()
+        }),
+        ..field.clone()
+    };
+    let err = setup(vec![vec![future]], deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        invalid("plot [0, 0] has a plant with a timer in the future")
+    );
+
+    let planted_meadow = Slot {
+        plant: Some(plant.clone()),
+        ..meadow.clone()
+    };
+    let err = setup(vec![vec![planted_meadow]], deps.as_mut()).unwrap_err();
+    assert_eq!(err, invalid("plot [0, 0] can only grow a plant on a field"));
+
+    let overwatered = Slot {
+        plant: Some(Plant {
+            watered: 6,
+            ..plant
+        }),
+        ..field.clone()
+    };
+    let err = setup(vec![vec![overwatered]], deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        invalid("plot [0, 0] has a plant watered more often than it has stages")
+    );
+
+    setup(
+        vec![vec![field, meadow.clone()], vec![meadow.clone(), meadow]],
+        deps.as_mut(),
+    )
+    .unwrap();
+    let farm = get_farm_profile("player", deps.as_ref());
+    assert_eq!(farm.size, 2);
+    assert_eq!(farm.plots[0][0].plant.as_ref().unwrap().watered_stages, 5);
+}
+
+#[test]
+fn patch_plots() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let height = env.block.height;
+    let plant: Plant = from_slice(
+        format!(
+            r#"{{"type":"sunflower","stages":5,"growth_period":10,"created_at":{},"watered_at":[1,1,1,1,1]}}"#,
+            height
+        )
+        .as_bytes(),
+    )
+    .unwrap();
+    let field = Slot {
+        r#type: SlotType::Field,
+        plant: Some(plant.clone()),
+        created_at: height,
+    };
+
+    let patch = |patches: Vec<(u8, u8, Slot)>, sender: &str, deps: DepsMut| {
+        let msg = ExecuteMsg::PatchPlots {
+            addr: Addr::unchecked("player"),
+            patches,
+        };
+        execute(deps, env.clone(), mock_info(sender, &[]), msg)
+    };
+
+    let err = patch(vec![(0, 0, field.clone())], "player", deps.as_mut()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let err = patch(vec![(0, 0, field.clone())], "creator", deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::NoFarm {
+            address: "player".to_string()
+        }
+    );
+
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info("player", &[]),
+        ExecuteMsg::Start {},
+    )
+    .unwrap();
+    let before = get_farm_profile("player", deps.as_ref());
+
+    let err = patch(vec![], "creator", deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFarm {
+            reason: "no plots to patch".to_string()
+        }
+    );
+
+    let size = before.size;
+    let err = patch(
+        vec![(size as u8, 0, field.clone())],
+        "creator",
+        deps.as_mut(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PlotOutOfBounds {
+            x: size,
+            y: 0,
+            size
+        }
+    );
+
+    let stalled = Slot {
+        plant: Some(Plant {
+            growth_period: 0,
+            ..plant.clone()
+        }),
+        ..field.clone()
+    };
+    let err = patch(vec![(1, 1, stalled)], "creator", deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFarm {
+            reason: "plot [1, 1] has a plant without stages or growth period".to_string()
+        }
+    );
+
+    let endless = Slot {
+        plant: Some(Plant {
+            stages: u64::MAX,
+            watered: u64::MAX,
+            ..plant.clone()
+        }),
+        ..field.clone()
+    };
+    let err = patch(vec![(1, 1, endless)], "creator", deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFarm {
+            reason: "plot [1, 1] has a plant with too many stages".to_string()
+        }
+    );
+
+    let nft = SeedNft {
+        collection: "collection_addr".to_string(),
+        token_id: "1".to_string(),
+    };
+    let seeded = Slot {
+        plant: Some(Plant {
+            seed_nft: Some(nft.clone()),
+            ..plant
+        }),
+        ..field.clone()
+    };
+    let err = patch(vec![(1, 1, seeded.clone())], "creator", deps.as_mut()).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InvalidFarm {
+            reason: "plot [1, 1] has a plant with a seed NFT".to_string()
+        }
+    );
+
+    let res = patch(vec![(1, 1, field.clone())], "creator", deps.as_mut()).unwrap();
+    assert_eq!(res.attributes[1].value, "1");
+    assert!(res.messages.is_empty());
+
+    let after = get_farm_profile("player", deps.as_ref());
+    assert_eq!(after.size, size);
+    let plant = after.plots[1][1].plant.as_ref().unwrap();
+    assert_eq!(plant.watered_stages, 5);
+    for x in 0..size {
+        for y in 0..size {
+            if (x, y) != (1, 1) {
+                assert_eq!(after.plots[x][y], before.plots[x][y]);
+            }
+        }
+    }
+
+    // The seed NFTs of replaced plants are refunded to the owner.
+    FARM_PLOTS
+        .save(deps.as_mut().storage, ("player", 1, 1), &seeded)
+        .unwrap();
+    let res = patch(vec![(1, 1, field.clone())], "creator", deps.as_mut()).unwrap();
+    let refund = transfer_nft_msg(&nft, "player".to_string()).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(refund.clone())]);
+
+    FARM_PLOTS
+        .save(deps.as_mut().storage, ("player", 1, 1), &seeded)
+        .unwrap();
+    let msg = ExecuteMsg::SetupFarm {
+        farm: FarmLayout {
+            plots: vec![vec![field]],
+        },
+        addr: Addr::unchecked("player"),
+    };
+    let res = execute(deps.as_mut(), env, mock_info("creator", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(refund)]);
+}
+
+#[test]
+fn pause() {
+    let (mut deps, env) = setup_test(Some(InstantiateMsg {
+        admin: None,
+        komple_mint_addr: None,
+        nois_proxy: None,
+        clock_mode: None,
+    }));
+
+    let admin = mock_info("creator", &[]);
+    let player = mock_info("player", &[]);
+    let pause_status = |deps: Deps| -> PauseStatus {
+        from_binary(&query(deps, mock_env(), QueryMsg::PauseStatus {}).unwrap()).unwrap()
+    };
+    assert_eq!(pause_status(deps.as_ref()), PauseStatus::default());
+
+    let msg = ExecuteMsg::SetPaused { paused: true };
+    let err = execute(deps.as_mut(), env.clone(), player.clone(), msg.clone()).unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+    assert!(pause_status(deps.as_ref()).paused);
+
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        player.clone(),
+        ExecuteMsg::Start {},
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Paused {});
+
+    // The admin can still fix the game while it is paused.
+    let msg = ExecuteMsg::UpdateMarketConfig {
+        config: MarketConfig { fee_bps: 100 },
+    };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+
+    let msg = ExecuteMsg::SetPaused { paused: false };
+    execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+    init_farm("player", deps.as_mut());
+
+    for action in [PausableAction::Harvest, PausableAction::Shop] {
+        let msg = ExecuteMsg::SetActionPaused {
+            action,
+            paused: true,
+        };
+        execute(deps.as_mut(), env.clone(), admin.clone(), msg).unwrap();
+    }
+    assert_eq!(
+        pause_status(deps.as_ref()).paused_actions,
+        vec![PausableAction::Harvest, PausableAction::Shop]
+    );
+
+    let msg = ExecuteMsg::Harvest { x: 0, y: 0 };
+    let err = execute(deps.as_mut(), env.clone(), player.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ActionPaused {
+            action: PausableAction::Harvest
+        }
+    );
+
+    let msg = ExecuteMsg::BuySeeds {
+        plant_type: "sunflower".to_string(),
+        amount: 1,
+    };
+    let err = execute(deps.as_mut(), env.clone(), player.clone(), msg).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::ActionPaused {
+            action: PausableAction::Shop
+        }
+    );
+
+    // Other actions of a batch go through.
+    let msg = ExecuteMsg::Batch {
+        actions: vec![
+            FarmAction::Till { x: 0, y: 0 },
+            FarmAction::Harvest { x: 0, y: 1 },
+        ],
+        skip_failures: true,
+    };
+    let res = execute(deps.as_mut(), env.clone(), player.clone(), msg).unwrap();
+    let results: Vec<FarmActionResult> = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(results[0].error, None);
+    assert_eq!(results[1].error, Some("Harvesting is paused".to_string()));
+
+    let msg = ExecuteMsg::SetActionPaused {
+        action: PausableAction::Harvest,
+        paused: false,
+    };
+    execute(deps.as_mut(), env, admin, msg).unwrap();
+    assert_eq!(
+        pause_status(deps.as_ref()).paused_actions,
+        vec![PausableAction::Shop]
+    );
+}
+
+fn arbitrary_plant(
+    created_at: u64,
+    growth_period: u64,
+    stages: u64,
+    watered: u64,
+    watering_tolerance: u64,
+) -> Plant {
+    Plant {
+        r#type: "sunflower".parse().unwrap(),
+        stages,
+        growth_period,
+        created_at,
+        watered,
+        last_watered_at: created_at,
+        watered_at: vec![],
+        komple: None,
+        watering_tolerance,
+        seed_nft: None,
+        effects: vec![CropEvent::Pests],
+        fertilizers: vec![Fertilizer::Growth],
+    }
+}
+
+proptest! {
+    #[test]
+    fn growth_math_never_panics(
+        block in any::<u64>(),
+        created_at in any::<u64>(),
+        growth_period in any::<u64>(),
+        stages in any::<u64>(),
+        watered in any::<u64>(),
+        watering_tolerance in any::<u64>(),
+        irrigated_since in any::<Option<u64>>(),
+    ) {
+        let plant = arbitrary_plant(created_at, growth_period, stages, watered, watering_tolerance);
+        prop_assert!(plant.get_current_stage(block) >= 1);
+        plant.watered_stages(block, irrigated_since);
+        plant.is_dead(block, irrigated_since);
+        plant.can_water(block, irrigated_since);
+        plant.can_harvest(block, irrigated_since);
+        plant.is_growing(block, irrigated_since);
+
+        let slot = Slot {
+            r#type: SlotType::Field,
+            plant: None,
+            created_at,
+        };
+        for clock in [ClockMode::BlockHeight, ClockMode::Seconds] {
+            slot.get_real_type(block, &clock);
+            slot.can_till(block, &clock, irrigated_since);
+        }
+    }
+
+    #[test]
+    fn future_plants_are_not_growing_yet(
+        block in 0..u64::MAX / 2,
+        ahead in 1..u64::MAX / 2,
+        growth_period in 1..1_000u64,
+        stages in 1..10u64,
+    ) {
+        let plant = arbitrary_plant(block + ahead, growth_period, stages, 1, 0);
+        prop_assert_eq!(plant.get_current_stage(block), 1);
+        prop_assert!(!plant.is_dead(block, None));
+
+        let slot = Slot {
+            r#type: SlotType::Field,
+            plant: None,
+            created_at: block + ahead,
+        };
+        prop_assert_eq!(slot.get_real_type(block, &ClockMode::BlockHeight), SlotType::Field);
+    }
 }